@@ -0,0 +1,110 @@
+use super::generator::TileRule;
+use hex::anyhow;
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Clone, Deserialize)]
+pub struct Biome {
+    pub id: String,
+    pub density: f64,
+    pub heat: (f64, f64),
+    pub richness: (f64, f64),
+    pub weights: HashMap<String, f64>,
+    #[serde(default)]
+    pub unlisted: f64,
+}
+
+impl Biome {
+    pub fn validate(&self, tiles: &[TileRule]) -> anyhow::Result<()> {
+        for (name, (min, max)) in [("heat", self.heat), ("richness", self.richness)] {
+            if min > max {
+                anyhow::bail!(
+                    "Biome \"{}\" has a {name} range of {min}..={max}, which is empty",
+                    self.id
+                );
+            }
+        }
+
+        for (tile, weight) in self
+            .weights
+            .iter()
+            .map(|(t, w)| (t.as_str(), *w))
+            .chain([("unlisted tiles", self.unlisted)])
+        {
+            if weight < 0.0 {
+                anyhow::bail!(
+                    "Biome \"{}\" has a negative weight of {weight} for {tile}",
+                    self.id
+                );
+            }
+        }
+
+        for tile in self.weights.keys() {
+            if !tiles.iter().any(|t| &t.id == tile) {
+                anyhow::bail!(
+                    "Biome \"{}\" uses tile \"{tile}\", which is not defined",
+                    self.id
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn check(&self, heat: f64, richness: f64) -> bool {
+        self.heat.0 <= heat
+            && self.heat.1 >= heat
+            && self.richness.0 <= richness
+            && self.richness.1 >= richness
+    }
+
+    pub fn weight(&self, id: &str) -> f64 {
+        self.weights.get(id).cloned().unwrap_or(self.unlisted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn biome(weights: serde_json::Value) -> anyhow::Result<Biome> {
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": "test",
+            "density": 0.0,
+            "heat": [-1.0, 1.0],
+            "richness": [-1.0, 1.0],
+            "weights": weights,
+            "unlisted": 0.25
+        }))?)
+    }
+
+    fn tile(id: &str) -> TileRule {
+        TileRule {
+            id: id.to_string(),
+            max: 1.0,
+            min: 0.0,
+            rand: 1.0,
+            deposit: None,
+        }
+    }
+
+    #[test]
+    fn weight_falls_back_to_unlisted() -> anyhow::Result<()> {
+        let biome = biome(serde_json::json!({ "metal": 3.0 }))?;
+
+        assert_eq!(biome.weight("metal"), 3.0);
+        assert_eq!(biome.weight("new_tile"), 0.25);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_rejects_undefined_tiles() -> anyhow::Result<()> {
+        let biome = biome(serde_json::json!({ "metal": 3.0 }))?;
+
+        assert!(biome.validate(&[tile("metal")]).is_ok());
+        assert!(biome.validate(&[tile("ice")]).is_err());
+
+        Ok(())
+    }
+}
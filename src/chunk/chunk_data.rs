@@ -1,18 +1,88 @@
 use super::CHUNK_SIZE;
-use hex::math::Vec2d;
+use crate::{
+    construct::{ConstructData, ItemData},
+    migration::{self, Migration},
+};
+use hex::{anyhow, math::Vec2d};
 use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const CHUNK_MIGRATIONS: &[Migration] = &[ChunkData::migrate_v0];
+pub const CHUNK_VERSION: u32 = CHUNK_MIGRATIONS.len() as u32;
 
 #[derive(Serialize, Deserialize)]
 pub struct ChunkData {
+    pub version: u32,
     pub position: [f32; 2],
     pub grid: Vec<Vec<Option<String>>>,
+    pub quantities: Vec<Vec<Option<u32>>>,
+    pub biome: String,
+    pub constructs: Vec<ConstructData>,
+    pub items: Vec<ItemData>,
 }
 
 impl ChunkData {
-    pub fn new(position: Vec2d) -> Self {
+    pub fn new(position: Vec2d, biome: String) -> Self {
         Self {
+            version: CHUNK_VERSION,
             position: position.0,
             grid: vec![vec![None; CHUNK_SIZE as usize]; CHUNK_SIZE as usize],
+            quantities: Self::empty_quantities(),
+            biome,
+            constructs: Vec::new(),
+            items: Vec::new(),
         }
     }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        migration::migrate(serde_json::from_str(content)?, CHUNK_MIGRATIONS)
+    }
+
+    fn migrate_v0(value: &mut Value) -> anyhow::Result<()> {
+        migration::insert_default(
+            value,
+            "quantities",
+            serde_json::to_value(Self::empty_quantities())?,
+        )?;
+        migration::insert_default(value, "biome", Value::String(String::new()))?;
+        migration::insert_default(value, "constructs", Value::Array(Vec::new()))?;
+        migration::insert_default(value, "items", Value::Array(Vec::new()))
+    }
+
+    pub fn empty_quantities() -> Vec<Vec<Option<u32>>> {
+        vec![vec![None; CHUNK_SIZE as usize]; CHUNK_SIZE as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_migrates_version_0() -> anyhow::Result<()> {
+        let grid =
+            vec![vec![Some("asteroid_1".to_string()); CHUNK_SIZE as usize]; CHUNK_SIZE as usize];
+        let data = ChunkData::parse(
+            &serde_json::json!({ "position": [1.0, -2.0], "grid": grid }).to_string(),
+        )?;
+
+        assert_eq!(data.version, CHUNK_VERSION);
+        assert_eq!(data.position, [1.0, -2.0]);
+        assert_eq!(data.grid, grid);
+        assert_eq!(data.quantities, ChunkData::empty_quantities());
+        assert!(data.biome.is_empty());
+        assert!(data.constructs.is_empty() && data.items.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_rejects_newer_versions() {
+        let data = ChunkData::parse(
+            &serde_json::json!({ "version": CHUNK_VERSION + 1, "position": [0.0, 0.0] })
+                .to_string(),
+        );
+
+        assert!(data.is_err());
+    }
 }
@@ -1,12 +1,18 @@
-use super::{Map, CHUNK_SIZE};
+use super::{Generator, Map, Region, RegionStore, TileCollider, CHUNK_SIZE};
 use crate::{
     chunk::{Chunk, ChunkData},
+    config::Config,
     construct::{Construct, ConstructData, Item, ItemData},
-    player::{state::GAME_MODE, State},
-    Tag, SAVE_DIR,
+    map_export::MapExport,
+    player::{
+        state::{GAME_MODE, MENU_MODE},
+        SaveData, State, World,
+    },
+    spatial::SpatialIndex,
+    Tag, ASTEROID_LAYER,
 };
 use hex::{
-    anyhow,
+    anyhow::{self, Context as _},
     assets::Texture,
     components::{Camera, Transform},
     ecs::{ev::Control, system_manager::System, ComponentManager, Context, EntityManager, Ev, Id},
@@ -19,103 +25,150 @@ use hex::{
     math::Vec2d,
 };
 use hex_instance::Instance;
-use hex_physics::Physical;
+use hex_physics::{Collider, Physical};
 use noise::NoiseFn;
 use rand::prelude::*;
 use std::{
     cell::OnceCell,
-    fs,
-    path::{Path, PathBuf},
+    collections::HashSet,
+    mem,
     rc::Rc,
+    sync::mpsc::{self, Receiver, Sender},
     time::{Duration, Instant},
 };
 
-pub const MAX_MAP_SIZE: u32 = 10000;
 pub const TILE_SIZE: u32 = 32;
 pub const CHUNK_DIST: f32 = 1.0;
-pub const MAX_CHUNK: u32 = MAX_MAP_SIZE / CHUNK_SIZE;
-pub const MIN_CHUNK: u32 = 2;
-pub const UNLOAD_BIAS: u32 = 8;
-pub const FRAME_LOAD_AMOUNT: usize = 1;
+pub const UNLOAD_BIAS: i32 = 8;
+pub const FRAME_LOAD_AMOUNT: usize = 4;
 pub const ASTEROID_UPDATE_TIME: Duration = Duration::from_millis(250);
 
 pub struct ChunkManager {
     player: OnceCell<Option<Id>>,
     camera: OnceCell<Option<Id>>,
+    index: OnceCell<Option<Id>>,
     check: Instant,
-    frame: Instant,
+    autosave: Instant,
+    mode: u32,
+    config: Config,
     map: Id,
+    regions: Option<RegionStore>,
+    sender: Sender<((i32, i32), anyhow::Result<ChunkData>)>,
+    receiver: Receiver<((i32, i32), anyhow::Result<ChunkData>)>,
 }
 
 impl ChunkManager {
-    pub fn new((em, cm): (&mut EntityManager, &mut ComponentManager)) -> Self {
+    pub fn new(config: Config, (em, cm): (&mut EntityManager, &mut ComponentManager)) -> Self {
         let map = em.add();
 
         cm.add(map, Map::default(), em);
         cm.add(map, Tag::new("map"), em);
 
+        let (sender, receiver) = mpsc::channel();
+
         Self {
             player: OnceCell::new(),
             camera: OnceCell::new(),
+            index: OnceCell::new(),
             check: Instant::now(),
-            frame: Instant::now(),
+            autosave: Instant::now(),
+            mode: MENU_MODE,
+            config,
             map,
+            regions: None,
+            sender,
+            receiver,
         }
     }
 
-    pub fn gen_chunk(&self, pos: Vec2d, state: &mut State) -> anyhow::Result<ChunkData> {
-        let mut data = ChunkData::new(pos);
+    pub fn gen_chunk(chunk @ (x, y): (i32, i32), generator: &Generator) -> ChunkData {
+        let pos = Vec2d::new(x as f32, y as f32);
+        let mut rng = generator.chunk_rng(chunk);
+        let biome = generator.biome(chunk);
+        let mut data = ChunkData::new(pos, biome.map(|b| b.id.clone()).unwrap_or_default());
 
         for i in 0..data.grid.len() {
             for j in 0..data.grid[i].len() {
                 let x = pos.x() as f64 * CHUNK_SIZE as f64 + i as f64;
                 let y = pos.y() as f64 * CHUNK_SIZE as f64 + j as f64;
-                let val = state.perlin.get([x / 25.0, y / 25.0, 0.0]);
-                let tiles: Vec<_> = state
+                let scale = generator.options.noise_scale;
+                let val = generator.perlin.get([x / scale, y / scale, 0.0])
+                    + biome.map(|b| b.density).unwrap_or_default()
+                    + generator.options.asteroid_density;
+                let tiles: Vec<_> = generator
                     .tiles
-                    .values()
-                    .filter_map(|t| {
-                        t.check(&mut state.rng, val)
-                            .map(|(id, t)| (Some(id.clone()), t))
-                    })
+                    .iter()
+                    .filter(|t| t.check(&mut rng, val))
                     .collect();
-                let (id, _) = tiles
-                    .choose(&mut state.rng)
-                    .cloned()
-                    .unwrap_or((None, &state.space));
 
-                data.grid[i][j] = id.as_ref().cloned();
+                if let Ok(tile) = tiles
+                    .choose_weighted(&mut rng, |t| biome.map(|b| b.weight(&t.id)).unwrap_or(1.0))
+                {
+                    data.grid[i][j] = Some(tile.id.clone());
+                    data.quantities[i][j] = generator.options.quantity(tile.quantity(val));
+                }
             }
         }
 
-        Ok(data)
-    }
+        if let Some(structure) = generator
+            .structures
+            .iter()
+            .find(|s| s.check(&mut rng, &data.biome))
+        {
+            structure.place(&mut rng, &mut data, &generator.tiles);
+        }
 
-    pub fn chunk_file((x, y): (u32, u32)) -> String {
-        format!("{x},{y}.json")
+        data
     }
 
-    pub fn load_chunk(
-        &mut self,
-        chunk @ (x, y): (u32, u32),
-        context: &Context,
-        state: &mut State,
-    ) -> anyhow::Result<(Chunk, Instance, Transform)> {
-        let chunks_dir = PathBuf::from(SAVE_DIR).join("chunks");
-        let path = chunks_dir.join(Self::chunk_file(chunk));
-        let data = if Path::exists(&path) {
-            let content = fs::read_to_string(path)?;
-            let data: ChunkData = serde_json::from_str(content.as_str())?;
-
-            data
+    pub fn read_chunk(
+        chunk: (i32, i32),
+        generator: &Generator,
+        regions: &RegionStore,
+    ) -> anyhow::Result<ChunkData> {
+        if let Some(data) = regions.get(chunk)? {
+            Ok(data)
         } else {
-            let data = self.gen_chunk(Vec2d::new(x as f32, y as f32), state)?;
-            let content = serde_json::to_string(&data)?;
+            let data = Self::gen_chunk(chunk, generator);
+
+            regions.insert(chunk, &data)?;
 
-            fs::write(path, content)?;
+            Ok(data)
+        }
+    }
 
-            data
+    pub fn queue_chunk(&self, chunk: (i32, i32), state: &State) {
+        if let Some(regions) = self.regions.clone() {
+            let generator = state.generator.clone();
+            let sender = self.sender.clone();
+
+            rayon::spawn(move || {
+                let _ = sender.send((chunk, Self::read_chunk(chunk, &generator, &regions)));
+            });
+        }
+    }
+
+    pub fn blit_tile(tile: &Texture, texture: &Texture, (i, j): (usize, usize)) {
+        let rect = BlitTarget {
+            left: i as u32 * TILE_SIZE,
+            bottom: j as u32 * TILE_SIZE,
+            width: TILE_SIZE as i32,
+            height: TILE_SIZE as i32,
         };
+
+        tile.buffer.as_surface().blit_whole_color_to(
+            &texture.buffer.as_surface(),
+            &rect,
+            MagnifySamplerFilter::Linear,
+        );
+    }
+
+    pub fn load_chunk(
+        position: (i32, i32),
+        data: ChunkData,
+        context: &Context,
+        state: &State,
+    ) -> anyhow::Result<(Chunk, Instance, Transform)> {
         let texture = Texture {
             buffer: Rc::new(Texture2d::empty(
                 &context.display,
@@ -128,7 +181,7 @@ impl ChunkManager {
             },
         };
 
-        let mut chunk = Chunk::new();
+        let mut chunk = Chunk::new(position, data.biome.clone());
 
         for i in 0..chunk.grid.len() {
             for j in 0..chunk.grid[i].len() {
@@ -136,19 +189,10 @@ impl ChunkManager {
                     .as_ref()
                     .and_then(|t| state.tiles.get(t).map(|t| (Some(t.id.clone()), &t.texture)))
                     .unwrap_or((None, &state.space));
-                let rect = BlitTarget {
-                    left: i as u32 * TILE_SIZE,
-                    bottom: j as u32 * TILE_SIZE,
-                    width: TILE_SIZE as i32,
-                    height: TILE_SIZE as i32,
-                };
-
-                t.buffer.as_surface().blit_whole_color_to(
-                    &texture.buffer.as_surface(),
-                    &rect,
-                    MagnifySamplerFilter::Linear,
-                );
 
+                Self::blit_tile(t, &texture, (i, j));
+
+                chunk.quantities[i][j] = id.as_ref().and(data.quantities[i][j]);
                 chunk.grid[i][j] = id;
             }
         }
@@ -165,10 +209,285 @@ impl ChunkManager {
         ))
     }
 
-    pub fn chunk_pos(pos: Vec2d) -> (u32, u32) {
+    pub fn build_colliders(
+        chunk: Id,
+        player: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) {
+        let old = cm
+            .get_mut::<Chunk>(chunk)
+            .map(|c| mem::take(&mut c.colliders))
+            .unwrap_or_default();
+
+        for e in old {
+            em.rm(e, cm);
+        }
+
+        let runs = cm
+            .get::<Chunk>(chunk)
+            .and_then(|chunk| Some((chunk, cm.get::<State>(player)?)))
+            .map(|(chunk, state)| {
+                let (x, y) = chunk.position;
+                let origin = Vec2d::new(
+                    ((x - 1) * CHUNK_SIZE as i32) as f32,
+                    ((y - 1) * CHUNK_SIZE as i32) as f32,
+                );
+                let mut runs = Vec::new();
+
+                for (i, column) in chunk.grid.iter().enumerate() {
+                    let mut start = None;
+
+                    for j in 0..=column.len() {
+                        let solid = column
+                            .get(j)
+                            .and_then(|t| state.tiles.get(t.as_ref()?))
+                            .map(|t| t.solid)
+                            .unwrap_or_default();
+
+                        match (solid, start) {
+                            (true, None) => start = Some(j),
+                            (false, Some(s)) => {
+                                let len = (j - s) as f32;
+
+                                runs.push((
+                                    origin + Vec2d::new(i as f32 + 0.5, s as f32 + len / 2.0),
+                                    Vec2d::new(1.0, len),
+                                ));
+
+                                start = None;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+
+                runs
+            })
+            .unwrap_or_default();
+        let colliders = runs
+            .into_iter()
+            .map(|(position, dims)| {
+                let e = em.add();
+
+                cm.add(e, TileCollider::new(dims), em);
+                cm.add(
+                    e,
+                    Collider::rect(
+                        dims,
+                        vec![ASTEROID_LAYER],
+                        vec![ASTEROID_LAYER],
+                        false,
+                        true,
+                    ),
+                    em,
+                );
+                cm.add(e, Transform::new(position, 0.0, Vec2d([1.0; 2]), true), em);
+
+                e
+            })
+            .collect();
+
+        if let Some(chunk) = cm.get_mut::<Chunk>(chunk) {
+            chunk.colliders = colliders;
+        }
+    }
+
+    pub fn chunk_pos(pos: Vec2d) -> (i32, i32) {
         let pos = pos / CHUNK_SIZE as f32;
 
-        (pos.x().ceil() as u32, pos.y().ceil() as u32)
+        (pos.x().floor() as i32 + 1, pos.y().floor() as i32 + 1)
+    }
+
+    pub fn tile_index(pos: Vec2d) -> (usize, usize) {
+        (
+            (pos.x().floor() as i32).rem_euclid(CHUNK_SIZE as i32) as usize,
+            (pos.y().floor() as i32).rem_euclid(CHUNK_SIZE as i32) as usize,
+        )
+    }
+
+    pub fn save_chunks(&self, entities: &[Id], cm: &mut ComponentManager) -> anyhow::Result<()> {
+        let dirty: Vec<_> = entities
+            .iter()
+            .filter_map(|e| {
+                let chunk = cm.get_mut::<Chunk>(*e)?;
+
+                chunk.dirty.then(|| {
+                    chunk.dirty = false;
+
+                    (chunk.position, chunk.data())
+                })
+            })
+            .collect();
+
+        if let Some(regions) = &self.regions {
+            regions.insert_all(dirty.iter().map(|(pos, data)| (*pos, data)))?;
+            regions.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn construct_data(
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> Vec<ConstructData> {
+        em.entities()
+            .filter_map(|e| {
+                let (tick_amount, mode, id, crafter, inventory, belt, storage, power, filter) =
+                    cm.get::<Construct>(e).map(|c| {
+                        (
+                            c.tick_amount,
+                            c.mode,
+                            c.id.clone(),
+                            c.crafter.clone(),
+                            c.inventory.clone(),
+                            c.belt.clone(),
+                            c.storage.clone(),
+                            c.power.clone(),
+                            c.filter.clone(),
+                        )
+                    })?;
+                let transform = cm.get::<Transform>(e)?;
+
+                Some(ConstructData {
+                    position: transform.position().0,
+                    rotation: transform.rotation(),
+                    id,
+                    tick_amount,
+                    mode,
+                    crafter,
+                    inventory,
+                    belt,
+                    storage,
+                    power,
+                    filter,
+                })
+            })
+            .collect()
+    }
+
+    pub fn export_map(
+        &self,
+        player: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        let entities: Vec<_> = em.entities().collect();
+
+        self.save_chunks(&entities, cm)?;
+
+        let constructs = Self::construct_data((em, cm));
+        let world = cm.get::<State>(player).and_then(|s| s.world.clone());
+        let player = cm.get::<Transform>(player).map(|t| t.position().0);
+
+        rayon::spawn(move || {
+            let Some(world) = world else {
+                return;
+            };
+            let path = MapExport::path(&world);
+
+            match MapExport::load().and_then(|m| m.export(&world, &constructs, player, &path)) {
+                Ok(()) => println!("Exported map to {}", path.display()),
+                Err(e) => eprintln!("Failed to export map: {e:?}"),
+            }
+        });
+
+        Ok(())
+    }
+
+    fn index(&self, (em, cm): (&mut EntityManager, &mut ComponentManager)) -> Option<Id> {
+        *self
+            .index
+            .get_or_init(|| Tag::new("spatial_index").find((em, cm)))
+    }
+
+    pub fn spawn_construct(
+        state: &State,
+        ConstructData {
+            id,
+            position,
+            rotation,
+            tick_amount,
+            mode,
+            crafter,
+            inventory,
+            belt,
+            storage,
+            power,
+            filter,
+        }: &ConstructData,
+        index: Option<Id>,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) {
+        if let Some((mut construct, instance)) = state.constructs.get(id).cloned() {
+            construct.tick_amount = *tick_amount;
+            construct.mode = *mode;
+
+            if crafter.is_some() {
+                construct.crafter = crafter.clone();
+            }
+
+            if let (Some(template), Some(saved)) = (construct.inventory.as_mut(), inventory) {
+                template.restore(saved);
+            }
+
+            if let (Some(template), Some(saved)) = (construct.belt.as_mut(), belt) {
+                *template = saved.restore();
+            }
+
+            if let (Some(template), Some(saved)) = (construct.storage.as_mut(), storage) {
+                template.restore(saved);
+            }
+
+            match (construct.power.as_mut(), power) {
+                (Some(template), Some(saved)) => template.restore(saved),
+                (Some(template), None) => template.legacy = true,
+                _ => {}
+            }
+
+            if construct.filter.is_some() && filter.is_some() {
+                construct.filter = filter.clone();
+            }
+
+            let position = Vec2d(*position);
+            let e = em.add();
+
+            cm.add(e, construct, em);
+            cm.add(e, instance, em);
+            cm.add(
+                e,
+                Transform::new(position, *rotation, Vec2d([1.0; 2]), true),
+                em,
+            );
+
+            if let Some(index) = index.and_then(|i| cm.get_mut::<SpatialIndex>(i)) {
+                index.insert(e, position);
+            }
+        }
+    }
+
+    pub fn spawn_item(
+        state: &State,
+        ItemData {
+            id,
+            position,
+            velocity,
+        }: &ItemData,
+        index: Option<Id>,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) {
+        if let Some((item, instance)) = state.items.get(id).cloned() {
+            let position = Vec2d(*position);
+            let velocity = Vec2d(*velocity);
+            let e = em.add();
+
+            cm.add(e, item, em);
+            cm.add(e, instance, em);
+            cm.add(e, Transform::new(position, 0.0, Vec2d([1.0; 2]), true), em);
+            cm.add(e, Physical::new(velocity, true), em);
+
+            if let Some(index) = index.and_then(|i| cm.get_mut::<SpatialIndex>(i)) {
+                index.insert(e, position);
+            }
+        }
     }
 
     pub fn load_objects(
@@ -176,69 +495,131 @@ impl ChunkManager {
         player: Id,
         (em, cm): (&mut EntityManager, &mut ComponentManager),
     ) {
+        let index = self.index((em, cm));
+
         if let Some(state) = cm.get::<State>(player).cloned() {
-            for ConstructData {
-                id,
-                position,
-                rotation,
-                tick_amount,
-                mode,
-            } in &state.save_data.constructs
-            {
-                if let Some((mut construct, instance)) = state.constructs.get(id).cloned() {
-                    construct.tick_amount = *tick_amount;
-                    construct.mode = *mode;
-
-                    let position = Vec2d(*position);
-                    let e = em.add();
-
-                    cm.add(e, construct, em);
-                    cm.add(e, instance, em);
-                    cm.add(
-                        e,
-                        Transform::new(position, *rotation, Vec2d([1.0; 2]), true),
-                        em,
-                    );
-                }
+            for c in &state.save_data.constructs {
+                Self::spawn_construct(&state, c, index, (em, cm));
             }
 
-            for ItemData {
-                id,
-                position,
-                velocity,
-            } in &state.save_data.items
-            {
-                if let Some((item, instance)) = state.items.get(id).cloned() {
-                    let position = Vec2d(*position);
-                    let velocity = Vec2d(*velocity);
-                    let e = em.add();
-
-                    cm.add(e, item, em);
-                    cm.add(e, instance, em);
-                    cm.add(e, Transform::new(position, 0.0, Vec2d([1.0; 2]), true), em);
-                    cm.add(e, Physical::new(velocity, true), em);
-                }
+            for i in &state.save_data.items {
+                Self::spawn_item(&state, i, index, (em, cm));
             }
         }
     }
-}
 
-impl System for ChunkManager {
-    fn init(
+    pub fn save_world(
+        &self,
+        player: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        if let Some(world) = cm.get::<State>(player).and_then(|s| s.world.clone()) {
+            world.backup(self.config.backups)?;
+        }
+
+        let entities: Vec<_> = em.entities().collect();
+
+        self.save_chunks(&entities, cm)?;
+
+        let constructs = Self::construct_data((em, cm));
+        let items = em
+            .entities()
+            .filter_map(|e| {
+                let id = cm.get::<Item>(e).map(|c| c.id.clone())?;
+                let physical = cm.get::<Physical>(e)?;
+                let transform = cm.get::<Transform>(e)?;
+
+                Some(ItemData {
+                    position: transform.position().0,
+                    velocity: physical.velocity().0,
+                    id,
+                })
+            })
+            .collect();
+
+        if let Some((p, v)) = cm
+            .get::<Transform>(player)
+            .map(|t| t.position())
+            .and_then(|p| Some((p, cm.get::<Physical>(player).map(|p| p.velocity())?)))
+        {
+            if let Some(state) = cm.get_mut::<State>(player) {
+                state.save_data.player_position = p.0;
+                state.save_data.player_velocity = v.0;
+                state.save_data.constructs = constructs;
+                state.save_data.items = items;
+
+                state.save()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn open_world(
         &mut self,
-        _: &mut Context,
+        player: Id,
+        world: World,
+        save_data: SaveData,
         (em, cm): (&mut EntityManager, &mut ComponentManager),
     ) -> anyhow::Result<()> {
-        if let Some(player) = *self
-            .player
-            .get_or_init(|| Tag::new("player").find((em, cm)))
+        if cm
+            .get::<State>(player)
+            .and_then(|s| s.world.as_ref())
+            .is_some()
         {
-            self.load_objects(player, (em, cm));
+            self.save_world(player, (em, cm))?;
+        }
+
+        let entities: Vec<_> = em
+            .entities()
+            .filter(|e| {
+                cm.get::<Chunk>(*e).is_some()
+                    || cm.get::<TileCollider>(*e).is_some()
+                    || cm.get::<Construct>(*e).is_some()
+                    || cm.get::<Item>(*e).is_some()
+            })
+            .collect();
+
+        for e in entities {
+            em.rm(e, cm);
         }
 
+        if let Some(index) = self
+            .index((em, cm))
+            .and_then(|i| cm.get_mut::<SpatialIndex>(i))
+        {
+            *index = SpatialIndex::default();
+        }
+
+        if let Some(map) = cm.get_mut::<Map>(self.map) {
+            map.load_queue.clear();
+            map.pending.clear();
+            map.loaded.clear();
+        }
+
+        (self.sender, self.receiver) = mpsc::channel();
+        self.regions = Some(RegionStore::new(world.regions_dir()));
+
+        if let Some(transform) = cm.get_mut::<Transform>(player) {
+            transform.set_position(Vec2d(save_data.player_position));
+        }
+
+        if let Some(physical) = cm.get_mut::<Physical>(player) {
+            physical.force = Vec2d(save_data.player_velocity);
+        }
+
+        if let Some(state) = cm.get_mut::<State>(player) {
+            state.open(world, save_data)?;
+            state.mode = GAME_MODE;
+        }
+
+        self.load_objects(player, (em, cm));
+
         Ok(())
     }
+}
 
+impl System for ChunkManager {
     fn update(
         &mut self,
         ev: &mut Ev,
@@ -258,37 +639,116 @@ impl System for ChunkManager {
                     event: Event::MainEventsCleared,
                     flow: _,
                 }) => {
+                    if let Some((world, save_data)) =
+                        cm.get_mut::<Map>(self.map).and_then(|m| m.open.take())
+                    {
+                        self.open_world(player, world, save_data, (em, cm))?;
+                    }
+
                     if let Some(mode) = cm.get::<State>(player).map(|p| p.mode) {
+                        if mode != self.mode {
+                            self.mode = mode;
+
+                            if mode == MENU_MODE {
+                                self.save_world(player, (em, cm))?;
+                            } else {
+                                self.autosave = Instant::now();
+                            }
+                        }
+
                         if mode == GAME_MODE {
                             if let Some((cam_dims, _)) =
                                 cm.get::<Camera>(camera).map(|c| c.dimensions())
                             {
                                 let now = Instant::now();
-                                let delta = now.duration_since(self.frame);
-
-                                self.frame = now;
-
-                                let chunks: Vec<_> = cm
+                                let queued: Vec<_> = cm
                                     .get_mut::<Map>(self.map)
                                     .map(|m| {
-                                        m.load_queue
-                                            .drain(
-                                                ..((FRAME_LOAD_AMOUNT
-                                                    * delta.as_secs_f32().ceil() as usize)
-                                                    .min(m.load_queue.len())),
-                                            )
-                                            .collect()
+                                        let queued: Vec<_> = m.load_queue.drain(..).collect();
+
+                                        m.pending.extend(queued.iter().cloned());
+
+                                        queued
                                     })
                                     .unwrap_or_default();
 
-                                for c in chunks {
-                                    if let Some((chunk, instance, transform)) =
-                                        if let Some(state) = cm.get_mut::<State>(player) {
-                                            Some(self.load_chunk(c, context, state)?)
+                                if let Some(state) = cm.get::<State>(player) {
+                                    for c in queued {
+                                        self.queue_chunk(c, state);
+                                    }
+                                }
+
+                                let chunks: Vec<_> =
+                                    self.receiver.try_iter().take(FRAME_LOAD_AMOUNT).collect();
+
+                                for (c, data) in chunks {
+                                    if let Some(map) = cm.get_mut::<Map>(self.map) {
+                                        map.pending.remove(&c);
+                                    }
+
+                                    let mut data = match data
+                                        .with_context(|| format!("Failed to load chunk {c:?}"))
+                                    {
+                                        Ok(data) => data,
+                                        Err(e) => {
+                                            if let Some(map) = cm.get_mut::<Map>(self.map) {
+                                                map.error = Some(format!("{e:#}"));
+                                            }
+
+                                            if let Some(state) = cm.get_mut::<State>(player) {
+                                                state.mode = MENU_MODE;
+                                            }
+
+                                            break;
+                                        }
+                                    };
+                                    let constructs = mem::take(&mut data.constructs);
+                                    let items = mem::take(&mut data.items);
+
+                                    if let Some((mut chunk, instance, transform)) =
+                                        if let Some(state) = cm.get::<State>(player) {
+                                            Some(Self::load_chunk(c, data, context, state)?)
                                         } else {
                                             None
                                         }
                                     {
+                                        if !(constructs.is_empty() && items.is_empty()) {
+                                            if let Some(state) = cm.get::<State>(player).cloned() {
+                                                let index = self.index((em, cm));
+                                                let occupied: Vec<_> = index
+                                                    .and_then(|i| cm.get::<SpatialIndex>(i))
+                                                    .map(|i| i.chunk(c))
+                                                    .unwrap_or_default()
+                                                    .into_iter()
+                                                    .filter(|e| cm.get::<Construct>(*e).is_some())
+                                                    .filter_map(|e| {
+                                                        Some(SpatialIndex::tile_pos(
+                                                            cm.get::<Transform>(e)?.position(),
+                                                        ))
+                                                    })
+                                                    .collect();
+
+                                                for construct in constructs.iter().filter(|d| {
+                                                    !occupied.contains(&SpatialIndex::tile_pos(
+                                                        Vec2d(d.position),
+                                                    ))
+                                                }) {
+                                                    Self::spawn_construct(
+                                                        &state,
+                                                        construct,
+                                                        index,
+                                                        (em, cm),
+                                                    );
+                                                }
+
+                                                for i in &items {
+                                                    Self::spawn_item(&state, i, index, (em, cm));
+                                                }
+                                            }
+
+                                            chunk.dirty = true;
+                                        }
+
                                         let e = em.add();
 
                                         cm.add(e, chunk, em);
@@ -298,9 +758,27 @@ impl System for ChunkManager {
                                         if let Some(map) = cm.get_mut::<Map>(self.map) {
                                             map.loaded.insert(c, e);
                                         }
+
+                                        Self::build_colliders(e, player, (em, cm));
                                     }
                                 }
 
+                                if cm
+                                    .get_mut::<Map>(self.map)
+                                    .map(|m| mem::take(&mut m.export))
+                                    .unwrap_or_default()
+                                {
+                                    self.export_map(player, (em, cm))?;
+                                }
+
+                                if now.duration_since(self.autosave)
+                                    >= self.config.autosave_interval()
+                                {
+                                    self.autosave = now;
+
+                                    self.save_world(player, (em, cm))?;
+                                }
+
                                 if now.duration_since(self.check) >= ASTEROID_UPDATE_TIME {
                                     self.check = now;
 
@@ -314,35 +792,37 @@ impl System for ChunkManager {
                                         }
                                     }
 
-                                    if let Some(player_chunk) =
+                                    if let Some((player_chunk, size_limit)) =
                                         cm.get::<Transform>(player).and_then(|t| {
-                                            t.active.then_some(Self::chunk_pos(t.position()))
+                                            Some((
+                                                t.active
+                                                    .then_some(Self::chunk_pos(t.position()))?,
+                                                cm.get::<State>(player)?.save_data.size_limit,
+                                            ))
                                         })
                                     {
                                         let offset_x = (cam_dims.x().ceil() / CHUNK_SIZE as f32
                                             * CHUNK_DIST)
                                             .ceil()
-                                            as u32;
+                                            as i32;
                                         let offset_y = (cam_dims.y().ceil() / CHUNK_SIZE as f32
                                             * CHUNK_DIST)
                                             .ceil()
-                                            as u32;
-                                        let min = (
-                                            player_chunk
-                                                .0
-                                                .checked_sub(offset_x)
-                                                .unwrap_or_default()
-                                                .max(MIN_CHUNK),
-                                            player_chunk
-                                                .1
-                                                .checked_sub(offset_y)
-                                                .unwrap_or_default()
-                                                .max(MIN_CHUNK),
-                                        );
-                                        let max = (
-                                            (player_chunk.0 + offset_x).min(MAX_CHUNK),
-                                            (player_chunk.1 + offset_y).min(MAX_CHUNK),
-                                        );
+                                            as i32;
+                                        let mut min =
+                                            (player_chunk.0 - offset_x, player_chunk.1 - offset_y);
+                                        let mut max =
+                                            (player_chunk.0 + offset_x, player_chunk.1 + offset_y);
+
+                                        if let Some(size_limit) = size_limit {
+                                            let lower =
+                                                Self::chunk_pos(Vec2d([-(size_limit as f32); 2]));
+                                            let upper =
+                                                Self::chunk_pos(Vec2d([size_limit as f32; 2]));
+
+                                            min = (min.0.max(lower.0), min.1.max(lower.1));
+                                            max = (max.0.min(upper.0 + 1), max.1.min(upper.1 + 1));
+                                        }
 
                                         for i in min.0..max.0 {
                                             for j in min.1..max.1 {
@@ -354,49 +834,54 @@ impl System for ChunkManager {
                                             }
                                         }
 
-                                        let entities: Vec<_> = em.entities().collect();
-
-                                        for e in entities {
-                                            if cm.get::<Chunk>(e).is_some() {
-                                                if let Some(position) =
-                                                    cm.get::<Transform>(e).and_then(|t| {
-                                                        t.active.then_some(Self::chunk_pos(
-                                                            t.position(),
-                                                        ))
+                                        let unloaded: Vec<_> = em
+                                            .entities()
+                                            .filter(|e| {
+                                                cm.get::<Chunk>(*e)
+                                                    .map(|c| {
+                                                        c.position.0 < min.0 - UNLOAD_BIAS
+                                                            || c.position.0 > max.0 + UNLOAD_BIAS
+                                                            || c.position.1 < min.1 - UNLOAD_BIAS
+                                                            || c.position.1 > max.1 + UNLOAD_BIAS
                                                     })
-                                                {
-                                                    if position.0
-                                                        < min
-                                                            .0
-                                                            .checked_sub(UNLOAD_BIAS)
-                                                            .unwrap_or_default()
-                                                        || position.0
-                                                            > max
-                                                                .0
-                                                                .checked_add(UNLOAD_BIAS)
-                                                                .unwrap_or(MAX_MAP_SIZE)
-                                                        || position.1
-                                                            < min
-                                                                .1
-                                                                .checked_sub(UNLOAD_BIAS)
-                                                                .unwrap_or_default()
-                                                        || position.1
-                                                            > max
-                                                                .1
-                                                                .checked_add(UNLOAD_BIAS)
-                                                                .unwrap_or(MAX_MAP_SIZE)
-                                                    {
-                                                        if let Some(map) =
-                                                            cm.get_mut::<Map>(self.map)
-                                                        {
-                                                            map.loaded.remove(&position);
-
-                                                            em.rm(e, cm);
-                                                        }
-                                                    }
+                                                    .unwrap_or_default()
+                                            })
+                                            .collect();
+
+                                        self.save_chunks(&unloaded, cm)?;
+
+                                        let evict = !unloaded.is_empty();
+
+                                        for e in unloaded {
+                                            if let Some((position, colliders)) = cm
+                                                .get::<Chunk>(e)
+                                                .map(|c| (c.position, c.colliders.clone()))
+                                            {
+                                                if let Some(map) = cm.get_mut::<Map>(self.map) {
+                                                    map.loaded.remove(&position);
                                                 }
+
+                                                for c in colliders {
+                                                    em.rm(c, cm);
+                                                }
+
+                                                em.rm(e, cm);
                                             }
                                         }
+
+                                        if let (Some(regions), Some(map)) = (
+                                            self.regions.as_ref().filter(|_| evict),
+                                            cm.get::<Map>(self.map),
+                                        ) {
+                                            let kept: HashSet<_> = map
+                                                .loaded
+                                                .keys()
+                                                .chain(&map.pending)
+                                                .map(|c| Region::region_pos(*c))
+                                                .collect();
+
+                                            regions.evict(|pos| kept.contains(&pos))?;
+                                        }
                                     }
                                 }
                             }
@@ -411,53 +896,7 @@ impl System for ChunkManager {
                         },
                     flow: _,
                 }) if *window_id == context.display.gl_window().window().id() => {
-                    if let Some((p, v, mut state)) = cm
-                        .get::<Transform>(player)
-                        .map(|t| t.position())
-                        .and_then(|p| {
-                            Some((
-                                p,
-                                cm.get::<Physical>(player).map(|p| p.velocity())?,
-                                cm.get_mut::<State>(player).cloned()?,
-                            ))
-                        })
-                    {
-                        state.save_data.player_position = p.0;
-                        state.save_data.player_velocity = v.0;
-                        state.save_data.constructs = em
-                            .entities()
-                            .filter_map(|e| {
-                                let (tick_amount, mode, id) = cm
-                                    .get::<Construct>(e)
-                                    .map(|c| (c.tick_amount, c.mode, c.id.clone()))?;
-                                let transform = cm.get::<Transform>(e)?;
-
-                                Some(ConstructData {
-                                    position: transform.position().0,
-                                    rotation: transform.rotation(),
-                                    id,
-                                    tick_amount,
-                                    mode,
-                                })
-                            })
-                            .collect();
-                        state.save_data.items = em
-                            .entities()
-                            .filter_map(|e| {
-                                let id = cm.get::<Item>(e).map(|c| c.id.clone())?;
-                                let physical = cm.get::<Physical>(e)?;
-                                let transform = cm.get::<Transform>(e)?;
-
-                                Some(ItemData {
-                                    position: transform.position().0,
-                                    velocity: physical.velocity().0,
-                                    id,
-                                })
-                            })
-                            .collect();
-
-                        state.save()?;
-                    }
+                    self.save_world(player, (em, cm))?;
                 }
                 _ => {}
             }
@@ -466,3 +905,46 @@ impl System for ChunkManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::GenOptions;
+    use std::collections::HashMap;
+
+    #[test]
+    fn gen_chunk_is_independent_of_order() -> anyhow::Result<()> {
+        let chunks: Vec<_> = (-3..=3)
+            .flat_map(|x| (-3..=3).map(move |y| (x, y)))
+            .collect();
+        let generator = Generator::load(42, GenOptions::default())?;
+        let expected: HashMap<_, _> = chunks
+            .iter()
+            .map(|c| (*c, ChunkManager::gen_chunk(*c, &generator)))
+            .collect();
+
+        assert!(expected
+            .values()
+            .any(|d| d.grid.iter().flatten().any(Option::is_some)));
+
+        let generator = Generator::load(42, GenOptions::default())?;
+
+        for c in chunks
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .chain(chunks.iter().step_by(2))
+            .rev()
+        {
+            let data = ChunkManager::gen_chunk(*c, &generator);
+
+            assert_eq!(data.grid, expected[c].grid, "grid of chunk {c:?}");
+            assert_eq!(
+                data.quantities, expected[c].quantities,
+                "quantities of chunk {c:?}"
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use serde_derive::{Deserialize, Serialize};
+
+pub const DEFAULT_NOISE_SCALE: f64 = 25.0;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct GenOptions {
+    pub noise_scale: f64,
+    pub asteroid_density: f64,
+    pub ore_richness: f64,
+}
+
+impl GenOptions {
+    pub fn quantity(&self, quantity: Option<u32>) -> Option<u32> {
+        quantity.map(|q| ((q as f64 * self.ore_richness).round() as u32).max(1))
+    }
+}
+
+impl Default for GenOptions {
+    fn default() -> Self {
+        Self {
+            noise_scale: DEFAULT_NOISE_SCALE,
+            asteroid_density: 0.0,
+            ore_richness: 1.0,
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::{Biome, GenOptions, Structure, TileManifest};
+use crate::{
+    player::state::{BIOMES_MANIFEST, STRUCTURES_MANIFEST},
+    util,
+};
+use hex::anyhow;
+use noise::{NoiseFn, Perlin};
+use rand::prelude::*;
+
+pub const BIOME_SCALE: f64 = 8.0;
+
+#[derive(Clone)]
+pub struct TileRule {
+    pub id: String,
+    pub max: f64,
+    pub min: f64,
+    pub rand: f64,
+    pub deposit: Option<u32>,
+}
+
+impl TileRule {
+    pub fn new(
+        TileManifest {
+            id,
+            min,
+            max,
+            rand,
+            deposit,
+            ..
+        }: &TileManifest,
+    ) -> Self {
+        Self {
+            id: id.clone(),
+            max: *max,
+            min: *min,
+            rand: *rand,
+            deposit: *deposit,
+        }
+    }
+
+    pub fn check(&self, rng: &mut StdRng, value: f64) -> bool {
+        rng.gen_bool(self.rand) && self.max >= value && self.min <= value
+    }
+
+    pub fn quantity(&self, value: f64) -> Option<u32> {
+        self.deposit.map(|deposit| {
+            let t = ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0);
+
+            1 + ((deposit - 1) as f64 * t).round() as u32
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct Generator {
+    pub seed: u32,
+    pub options: GenOptions,
+    pub perlin: Perlin,
+    pub heat: Perlin,
+    pub richness: Perlin,
+    pub tiles: Vec<TileRule>,
+    pub biomes: Vec<Biome>,
+    pub structures: Vec<Structure>,
+}
+
+impl Generator {
+    pub fn new<I, S>(
+        seed: u32,
+        options: GenOptions,
+        tiles: I,
+        biomes: Vec<Biome>,
+        structures: S,
+    ) -> Self
+    where
+        I: IntoIterator<Item = TileRule>,
+        S: IntoIterator<Item = Structure>,
+    {
+        let mut tiles: Vec<_> = tiles.into_iter().collect();
+        let mut structures: Vec<_> = structures.into_iter().collect();
+
+        tiles.sort_by(|a, b| a.id.cmp(&b.id));
+        structures.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Self {
+            seed,
+            options,
+            perlin: Perlin::new(seed),
+            heat: Perlin::new(seed.wrapping_add(1)),
+            richness: Perlin::new(seed.wrapping_add(2)),
+            tiles,
+            biomes,
+            structures,
+        }
+    }
+
+    pub fn load(seed: u32, options: GenOptions) -> anyhow::Result<Self> {
+        let tiles: Vec<_> = TileManifest::load_all()?
+            .iter()
+            .map(TileRule::new)
+            .collect();
+        let mut biomes: Vec<Biome> = Vec::new();
+
+        for biome in util::load_manifest::<Biome>(BIOMES_MANIFEST)? {
+            biome.validate(&tiles)?;
+
+            if biomes.iter().any(|b| b.id == biome.id) {
+                anyhow::bail!("Biome \"{}\" is defined more than once", biome.id);
+            }
+
+            biomes.push(biome);
+        }
+
+        let mut structures: Vec<Structure> = Vec::new();
+
+        for structure in util::load_manifest::<Structure>(STRUCTURES_MANIFEST)? {
+            structure.validate()?;
+
+            for biome in &structure.biomes {
+                if !biomes.iter().any(|b| &b.id == biome) {
+                    anyhow::bail!(
+                        "Structure \"{}\" uses biome \"{biome}\", which is not defined",
+                        structure.id
+                    );
+                }
+            }
+
+            for tile in structure.tiles.iter().filter_map(|t| t.tile.as_ref()) {
+                if !tiles.iter().any(|t| &t.id == tile) {
+                    anyhow::bail!(
+                        "Structure \"{}\" uses tile \"{tile}\", which is not defined",
+                        structure.id
+                    );
+                }
+            }
+
+            if structures.iter().any(|s| s.id == structure.id) {
+                anyhow::bail!("Structure \"{}\" is defined more than once", structure.id);
+            }
+
+            structures.push(structure);
+        }
+
+        Ok(Self::new(seed, options, tiles, biomes, structures))
+    }
+
+    pub fn biome(&self, (x, y): (i32, i32)) -> Option<&Biome> {
+        let x = x as f64 / BIOME_SCALE;
+        let y = y as f64 / BIOME_SCALE;
+        let heat = self.heat.get([x, y, 0.0]);
+        let richness = self.richness.get([x, y, 0.0]);
+
+        self.biomes.iter().find(|b| b.check(heat, richness))
+    }
+
+    pub fn chunk_rng(&self, (x, y): (i32, i32)) -> StdRng {
+        let mut seed = <StdRng as SeedableRng>::Seed::default();
+
+        seed[0..4].copy_from_slice(&self.seed.to_le_bytes());
+        seed[4..8].copy_from_slice(&x.to_le_bytes());
+        seed[8..12].copy_from_slice(&y.to_le_bytes());
+
+        StdRng::from_seed(seed)
+    }
+}
@@ -1,15 +1,23 @@
+use crate::player::{SaveData, World};
 use hex::ecs::{component_manager::Component, Id};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct Map {
-    pub load_queue: Vec<(u32, u32)>,
-    pub loaded: HashMap<(u32, u32), Id>,
+    pub load_queue: Vec<(i32, i32)>,
+    pub pending: HashSet<(i32, i32)>,
+    pub loaded: HashMap<(i32, i32), Id>,
+    pub export: bool,
+    pub open: Option<(World, SaveData)>,
+    pub error: Option<String>,
 }
 
 impl Map {
-    pub fn queue_load(&mut self, chunk: (u32, u32)) {
-        if !(self.load_queue.contains(&chunk) || self.loaded.contains_key(&chunk)) {
+    pub fn queue_load(&mut self, chunk: (i32, i32)) {
+        if !(self.load_queue.contains(&chunk)
+            || self.pending.contains(&chunk)
+            || self.loaded.contains_key(&chunk))
+        {
             self.load_queue.push(chunk);
         }
     }
@@ -1,29 +1,133 @@
+pub mod biome;
 pub mod chunk_data;
 pub mod chunk_manager;
+pub mod gen_options;
+pub mod generator;
 pub mod map;
+pub mod region;
+pub mod structure;
 pub mod tile;
+pub mod tile_collider;
 pub mod tile_data;
+pub mod tile_manifest;
 
-pub use chunk_data::ChunkData;
+pub use biome::Biome;
+pub use chunk_data::{ChunkData, CHUNK_VERSION};
 pub use chunk_manager::ChunkManager;
+pub use gen_options::GenOptions;
+pub use generator::Generator;
 pub use map::Map;
+pub use region::{Region, RegionStore};
+pub use structure::Structure;
 pub use tile::Tile;
+pub use tile_collider::TileCollider;
+pub use tile_manifest::TileManifest;
 
-use hex::ecs::component_manager::Component;
+use hex::ecs::{component_manager::Component, Id};
 
 pub const CHUNK_SIZE: u32 = 16;
 
 #[derive(Clone)]
 pub struct Chunk {
+    pub position: (i32, i32),
+    pub biome: String,
     pub grid: Vec<Vec<Option<String>>>,
+    pub quantities: Vec<Vec<Option<u32>>>,
+    pub dirty: bool,
+    pub colliders: Vec<Id>,
 }
 
 impl Chunk {
-    pub fn new() -> Self {
+    pub fn new(position: (i32, i32), biome: String) -> Self {
         Self {
+            position,
+            biome,
             grid: vec![vec![None; CHUNK_SIZE as usize]; CHUNK_SIZE as usize],
+            quantities: vec![vec![None; CHUNK_SIZE as usize]; CHUNK_SIZE as usize],
+            dirty: false,
+            colliders: Vec::new(),
+        }
+    }
+
+    pub fn mine(&mut self, (x, y): (usize, usize)) -> Option<(String, bool)> {
+        let id = self.grid.get(x)?.get(y)?.clone()?;
+        let quantity = self.quantities.get_mut(x)?.get_mut(y)?;
+
+        if let Some(q) = quantity {
+            *q = q.saturating_sub(1);
+            self.dirty = true;
+
+            if *q == 0 {
+                *quantity = None;
+                self.grid[x][y] = None;
+
+                return Some((id, true));
+            }
+        }
+
+        Some((id, false))
+    }
+
+    pub fn place(&mut self, (x, y): (usize, usize), id: &str) -> bool {
+        match self.grid.get_mut(x).and_then(|c| c.get_mut(y)) {
+            Some(tile @ None) => {
+                *tile = Some(id.to_string());
+                self.quantities[x][y] = None;
+                self.dirty = true;
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn dig(&mut self, (x, y): (usize, usize)) -> Option<String> {
+        // Ore deposits only yield their ore to miners, so they can't be dug out.
+        if self.quantities.get(x)?.get(y)?.is_some() {
+            return None;
+        }
+
+        let id = self.grid.get_mut(x)?.get_mut(y)?.take()?;
+
+        self.dirty = true;
+
+        Some(id)
+    }
+
+    pub fn data(&self) -> ChunkData {
+        let (x, y) = self.position;
+
+        ChunkData {
+            version: CHUNK_VERSION,
+            position: [x as f32, y as f32],
+            grid: self.grid.clone(),
+            quantities: self.quantities.clone(),
+            biome: self.biome.clone(),
+            constructs: Vec::new(),
+            items: Vec::new(),
         }
     }
 }
 
 impl Component for Chunk {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dig_leaves_ore_deposits() {
+        let mut chunk = Chunk::new((0, 0), String::new());
+
+        chunk.grid[1][2] = Some("metal".to_string());
+        chunk.quantities[1][2] = Some(1);
+        chunk.grid[3][4] = Some("asteroid_1".to_string());
+
+        assert_eq!(chunk.dig((1, 2)), None);
+        assert_eq!(chunk.dig((3, 4)).as_deref(), Some("asteroid_1"));
+        assert!(chunk.dirty);
+        assert_eq!(chunk.mine((1, 2)), Some(("metal".to_string(), true)));
+        assert_eq!(chunk.dig((1, 2)), None);
+        assert!(chunk.place((1, 2), "asteroid_1"));
+    }
+}
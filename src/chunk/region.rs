@@ -0,0 +1,492 @@
+use super::{ChunkData, CHUNK_SIZE};
+use crate::{
+    construct::{ConstructData, ItemData},
+    util,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use hex::{
+    anyhow::{self, Context},
+    math::Vec2d,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fs::{self, File},
+    io::{BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+pub const REGION_SIZE: i32 = 32;
+pub const REGION_MAGIC: &[u8; 4] = b"PRGN";
+pub const REGION_VERSION: u32 = 1;
+
+pub type RegionCell = Arc<Mutex<Option<Region>>>;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Region {
+    pub chunks: HashMap<(i32, i32), RegionChunk>,
+}
+
+impl Region {
+    pub fn dir(world_dir: &Path) -> PathBuf {
+        world_dir.join("regions")
+    }
+
+    pub fn region_pos((x, y): (i32, i32)) -> (i32, i32) {
+        (x.div_euclid(REGION_SIZE), y.div_euclid(REGION_SIZE))
+    }
+
+    pub fn region_file((x, y): (i32, i32)) -> String {
+        format!("{x},{y}.region")
+    }
+
+    pub fn saved(dir: &Path) -> anyhow::Result<Vec<(i32, i32)>> {
+        let mut regions = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            if let Some(pos) = entry?
+                .path()
+                .file_name()
+                .and_then(|n| n.to_str()?.strip_suffix(".region"))
+                .and_then(|n| {
+                    let (x, y) = n.split_once(',')?;
+
+                    Some((x.parse().ok()?, y.parse().ok()?))
+                })
+            {
+                regions.push(pos);
+            }
+        }
+
+        Ok(regions)
+    }
+
+    pub fn load(dir: &Path, pos: (i32, i32)) -> anyhow::Result<Self> {
+        let path = dir.join(Self::region_file(pos));
+
+        if !Path::exists(&path) {
+            return Ok(Self::default());
+        }
+
+        let mut bytes = Vec::new();
+
+        GzDecoder::new(BufReader::new(File::open(&path)?)).read_to_end(&mut bytes)?;
+
+        let bytes = bytes
+            .strip_prefix(REGION_MAGIC)
+            .with_context(|| format!("Region {} is not a region file", path.display()))?;
+        let version: u32 = bincode::deserialize(bytes)?;
+
+        match version {
+            REGION_VERSION => Ok(bincode::deserialize(bytes.get(4..).unwrap_or_default())?),
+            _ => anyhow::bail!(
+                "Region {} has version {version}, which is not supported by this build",
+                path.display()
+            ),
+        }
+    }
+
+    pub fn save(&self, dir: &Path, pos: (i32, i32)) -> anyhow::Result<()> {
+        util::write_atomic_with(&dir.join(Self::region_file(pos)), |w| {
+            let mut encoder = GzEncoder::new(w, Compression::default());
+
+            encoder.write_all(REGION_MAGIC)?;
+
+            bincode::serialize_into(&mut encoder, &(REGION_VERSION, self))?;
+
+            encoder.finish()?;
+
+            Ok(())
+        })
+    }
+
+    pub fn get(&self, chunk @ (x, y): (i32, i32)) -> Option<ChunkData> {
+        self.chunks
+            .get(&chunk)
+            .map(|c| c.decode(Vec2d::new(x as f32, y as f32)))
+    }
+
+    pub fn insert(&mut self, chunk: (i32, i32), data: &ChunkData) {
+        self.chunks.insert(chunk, RegionChunk::encode(data));
+    }
+
+    pub fn convert_chunks(world_dir: &Path) -> anyhow::Result<()> {
+        let chunks_dir = world_dir.join("chunks");
+        let dir = Self::dir(world_dir);
+
+        if !Path::exists(&chunks_dir) {
+            return Ok(());
+        }
+
+        let mut regions: HashMap<(i32, i32), Region> = HashMap::new();
+        let mut converted = Vec::new();
+
+        for entry in fs::read_dir(&chunks_dir)? {
+            let path = entry?.path();
+
+            if let Some(chunk) = path
+                .file_name()
+                .and_then(|n| n.to_str()?.strip_suffix(".json"))
+                .and_then(|n| {
+                    let (x, y) = n.split_once(',')?;
+
+                    Some((x.parse().ok()?, y.parse().ok()?))
+                })
+            {
+                let data = ChunkData::parse(&fs::read_to_string(&path)?)
+                    .with_context(|| format!("Failed to convert chunk {}", path.display()))?;
+                let region_pos = Self::region_pos(chunk);
+                let region = match regions.entry(region_pos) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => e.insert(Self::load(&dir, region_pos)?),
+                };
+
+                if !region.chunks.contains_key(&chunk) {
+                    region.insert(chunk, &data);
+                }
+
+                converted.push(path);
+            }
+        }
+
+        fs::create_dir_all(&dir)?;
+
+        for (pos, region) in &regions {
+            region.save(&dir, *pos)?;
+        }
+
+        for path in converted {
+            fs::remove_file(path)?;
+        }
+
+        if fs::read_dir(&chunks_dir)?.next().is_none() {
+            fs::remove_dir(chunks_dir)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct RegionStore {
+    dir: PathBuf,
+    regions: Arc<Mutex<HashMap<(i32, i32), RegionCell>>>,
+    dirty: Arc<Mutex<HashSet<(i32, i32)>>>,
+}
+
+impl RegionStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            regions: Default::default(),
+            dirty: Default::default(),
+        }
+    }
+
+    pub fn get(&self, chunk: (i32, i32)) -> anyhow::Result<Option<ChunkData>> {
+        self.with_region(Region::region_pos(chunk), |r| r.get(chunk))
+    }
+
+    pub fn insert(&self, chunk: (i32, i32), data: &ChunkData) -> anyhow::Result<()> {
+        let pos = Region::region_pos(chunk);
+
+        self.with_region(pos, |r| r.insert(chunk, data))?;
+        self.mark_dirty(pos)
+    }
+
+    pub fn insert_all<'a, I>(&self, chunks: I) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = ((i32, i32), &'a ChunkData)>,
+    {
+        let mut regions: HashMap<(i32, i32), Vec<_>> = HashMap::new();
+
+        for (chunk, data) in chunks {
+            regions
+                .entry(Region::region_pos(chunk))
+                .or_default()
+                .push((chunk, data));
+        }
+
+        for (pos, chunks) in regions {
+            self.with_region(pos, |r| {
+                for (chunk, data) in chunks {
+                    r.insert(chunk, data);
+                }
+            })?;
+            self.mark_dirty(pos)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&self) -> anyhow::Result<()> {
+        let dirty: Vec<_> = self
+            .dirty
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Region store lock was poisoned"))?
+            .drain()
+            .collect();
+
+        for pos in dirty {
+            if let Err(e) = self.with_region(pos, |r| r.save(&self.dir, pos))? {
+                self.mark_dirty(pos)?;
+
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn evict<F>(&self, keep: F) -> anyhow::Result<()>
+    where
+        F: Fn((i32, i32)) -> bool,
+    {
+        let dirty = self
+            .dirty
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Region store lock was poisoned"))?;
+
+        self.regions
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Region store lock was poisoned"))?
+            .retain(|pos, _| dirty.contains(pos) || keep(*pos));
+
+        Ok(())
+    }
+
+    fn mark_dirty(&self, pos: (i32, i32)) -> anyhow::Result<()> {
+        self.dirty
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Region store lock was poisoned"))?
+            .insert(pos);
+
+        Ok(())
+    }
+
+    fn with_region<F, T>(&self, pos: (i32, i32), f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(&mut Region) -> T,
+    {
+        let region = self
+            .regions
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Region store lock was poisoned"))?
+            .entry(pos)
+            .or_default()
+            .clone();
+        let mut guard = region
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Region {pos:?} lock was poisoned"))?;
+        let mut region = match guard.take() {
+            Some(region) => region,
+            None => Region::load(&self.dir, pos)?,
+        };
+        let res = f(&mut region);
+
+        *guard = Some(region);
+
+        Ok(res)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RegionChunk {
+    pub biome: String,
+    pub palette: Vec<String>,
+    pub tiles: Vec<u16>,
+    pub quantities: Vec<Option<u32>>,
+    pub constructs: Vec<RegionConstruct>,
+    pub items: Vec<ItemData>,
+}
+
+impl RegionChunk {
+    pub fn encode(data: &ChunkData) -> Self {
+        let mut palette: Vec<String> = Vec::new();
+        let tiles = data
+            .grid
+            .iter()
+            .flatten()
+            .map(|id| {
+                id.as_ref()
+                    .map(|id| {
+                        let index = palette.iter().position(|p| p == id).unwrap_or_else(|| {
+                            palette.push(id.clone());
+
+                            palette.len() - 1
+                        });
+
+                        index as u16 + 1
+                    })
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        Self {
+            biome: data.biome.clone(),
+            palette,
+            tiles,
+            quantities: data.quantities.iter().flatten().cloned().collect(),
+            constructs: data.constructs.iter().map(RegionConstruct::from).collect(),
+            items: data.items.clone(),
+        }
+    }
+
+    pub fn decode(&self, position: Vec2d) -> ChunkData {
+        let mut data = ChunkData::new(position, self.biome.clone());
+
+        data.constructs = self.constructs.iter().cloned().map(Into::into).collect();
+        data.items = self.items.clone();
+
+        for (i, t) in self.tiles.iter().enumerate() {
+            let x = i / CHUNK_SIZE as usize;
+            let y = i % CHUNK_SIZE as usize;
+
+            if let Some(column) = data.grid.get_mut(x) {
+                column[y] = t
+                    .checked_sub(1)
+                    .and_then(|t| self.palette.get(t as usize).cloned());
+            }
+
+            if let Some(column) = data.quantities.get_mut(x) {
+                column[y] = self.quantities.get(i).cloned().flatten();
+            }
+        }
+
+        data
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RegionConstruct {
+    pub id: String,
+    pub position: [f32; 2],
+    pub rotation: f32,
+    pub mode: Option<bool>,
+    pub tick_amount: u32,
+}
+
+impl From<&ConstructData> for RegionConstruct {
+    fn from(data: &ConstructData) -> Self {
+        Self {
+            id: data.id.clone(),
+            position: data.position,
+            rotation: data.rotation,
+            mode: data.mode,
+            tick_amount: data.tick_amount,
+        }
+    }
+}
+
+impl From<RegionConstruct> for ConstructData {
+    fn from(
+        RegionConstruct {
+            id,
+            position,
+            rotation,
+            mode,
+            tick_amount,
+        }: RegionConstruct,
+    ) -> Self {
+        Self {
+            mode,
+            tick_amount,
+            ..Self::new(id, position, rotation)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> anyhow::Result<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("paraselene_{name}_{}", std::process::id()));
+
+        fs::create_dir_all(&dir)?;
+
+        Ok(dir)
+    }
+
+    fn write_gz(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+        let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+
+        encoder.write_all(bytes)?;
+        encoder.finish()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_reads_saved_regions() -> anyhow::Result<()> {
+        let dir = temp_dir("region_saved")?;
+        let mut data = ChunkData::new(Vec2d::new(1.0, 2.0), "field".to_string());
+
+        data.grid[3][4] = Some("asteroid_1".to_string());
+        data.quantities[3][4] = Some(9);
+
+        let mut region = Region::default();
+
+        region.insert((1, 2), &data);
+        region.save(&dir, (0, 0))?;
+
+        let loaded = Region::load(&dir, (0, 0))?
+            .get((1, 2))
+            .context("Expected chunk (1, 2)")?;
+
+        fs::remove_dir_all(dir)?;
+
+        assert_eq!(loaded.grid, data.grid);
+        assert_eq!(loaded.quantities, data.quantities);
+
+        Ok(())
+    }
+
+    #[test]
+    fn store_reloads_evicted_regions() -> anyhow::Result<()> {
+        let dir = temp_dir("region_evict")?;
+        let store = RegionStore::new(dir.clone());
+        let mut data = ChunkData::new(Vec2d::new(1.0, 2.0), "field".to_string());
+
+        data.grid[5][6] = Some("metal".to_string());
+        data.quantities[5][6] = Some(12);
+        store.insert((1, 2), &data)?;
+        store.evict(|_| false)?;
+
+        let dirty_kept = store.regions.lock().map(|r| r.len()).unwrap_or_default();
+
+        store.flush()?;
+        store.evict(|_| false)?;
+
+        let flushed_kept = store.regions.lock().map(|r| r.len()).unwrap_or_default();
+        let loaded = store.get((1, 2))?.context("Expected chunk (1, 2)");
+
+        fs::remove_dir_all(dir)?;
+
+        let loaded = loaded?;
+
+        assert_eq!((dirty_kept, flushed_kept), (1, 0));
+        assert_eq!(loaded.grid, data.grid);
+        assert_eq!(loaded.quantities, data.quantities);
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_rejects_newer_versions() -> anyhow::Result<()> {
+        let dir = temp_dir("region_newer")?;
+        let mut bytes = REGION_MAGIC.to_vec();
+
+        bytes.extend(bincode::serialize(&(REGION_VERSION + 1))?);
+        write_gz(&dir.join(Region::region_file((0, 0))), &bytes)?;
+
+        let region = Region::load(&dir, (0, 0));
+
+        fs::remove_dir_all(dir)?;
+
+        assert!(region.is_err());
+
+        Ok(())
+    }
+}
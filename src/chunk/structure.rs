@@ -0,0 +1,113 @@
+use super::{generator::TileRule, ChunkData, CHUNK_SIZE};
+use crate::construct::{ConstructData, ItemData};
+use hex::anyhow;
+use rand::prelude::*;
+use serde_derive::Deserialize;
+
+#[derive(Clone, Deserialize)]
+pub struct Structure {
+    pub id: String,
+    pub chance: f64,
+    #[serde(default)]
+    pub biomes: Vec<String>,
+    #[serde(default)]
+    pub tiles: Vec<StructureTile>,
+    #[serde(default)]
+    pub constructs: Vec<StructureConstruct>,
+    #[serde(default)]
+    pub items: Vec<StructureItem>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct StructureTile {
+    pub position: [u32; 2],
+    pub tile: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct StructureConstruct {
+    pub position: [u32; 2],
+    pub id: String,
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct StructureItem {
+    pub position: [u32; 2],
+    pub id: String,
+}
+
+impl Structure {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(0.0..=1.0).contains(&self.chance) {
+            anyhow::bail!(
+                "Structure \"{}\" has a chance of {}, which is not within 0..=1",
+                self.id,
+                self.chance
+            );
+        }
+
+        let (w, h) = self.dims();
+
+        if w > CHUNK_SIZE || h > CHUNK_SIZE {
+            anyhow::bail!(
+                "Structure \"{}\" is {w}x{h} tiles, which does not fit in a chunk",
+                self.id
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn dims(&self) -> (u32, u32) {
+        self.tiles
+            .iter()
+            .map(|t| t.position)
+            .chain(self.constructs.iter().map(|c| c.position))
+            .chain(self.items.iter().map(|i| i.position))
+            .fold((0, 0), |(w, h), [x, y]| (w.max(x + 1), h.max(y + 1)))
+    }
+
+    pub fn check(&self, rng: &mut StdRng, biome: &str) -> bool {
+        (self.biomes.is_empty() || self.biomes.iter().any(|b| b == biome))
+            && rng.gen_bool(self.chance)
+    }
+
+    pub fn place(&self, rng: &mut StdRng, data: &mut ChunkData, rules: &[TileRule]) {
+        let (w, h) = self.dims();
+        let offset = (
+            rng.gen_range(0..=CHUNK_SIZE - w),
+            rng.gen_range(0..=CHUNK_SIZE - h),
+        );
+        let origin = [
+            (data.position[0] - 1.0) * CHUNK_SIZE as f32 + offset.0 as f32 + 0.5,
+            (data.position[1] - 1.0) * CHUNK_SIZE as f32 + offset.1 as f32 + 0.5,
+        ];
+        let world = |[x, y]: [u32; 2]| [origin[0] + x as f32, origin[1] + y as f32];
+
+        for StructureTile {
+            position: [x, y],
+            tile,
+        } in &self.tiles
+        {
+            let (i, j) = ((offset.0 + x) as usize, (offset.1 + y) as usize);
+
+            data.grid[i][j] = tile.clone();
+            data.quantities[i][j] = tile
+                .as_ref()
+                .and_then(|t| rules.iter().find(|r| &r.id == t)?.deposit);
+        }
+
+        data.constructs.extend(
+            self.constructs
+                .iter()
+                .map(|c| ConstructData::new(c.id.clone(), world(c.position), c.rotation)),
+        );
+        data.items.extend(self.items.iter().map(|i| ItemData {
+            position: world(i.position),
+            velocity: [0.0; 2],
+            id: i.id.clone(),
+        }));
+    }
+}
@@ -0,0 +1,14 @@
+use hex::{ecs::component_manager::Component, math::Vec2d};
+
+#[derive(Clone)]
+pub struct TileCollider {
+    pub dims: Vec2d,
+}
+
+impl TileCollider {
+    pub fn new(dims: Vec2d) -> Self {
+        Self { dims }
+    }
+}
+
+impl Component for TileCollider {}
@@ -0,0 +1,88 @@
+use crate::{player::state::TILES_MANIFEST, util};
+use hex::anyhow::{self, Context};
+use serde_derive::Deserialize;
+
+#[derive(Deserialize)]
+pub struct TileManifest {
+    pub id: String,
+    pub texture: String,
+    pub min: f64,
+    pub max: f64,
+    pub rand: f64,
+    #[serde(default)]
+    pub deposit: Option<u32>,
+    #[serde(default = "default_solid")]
+    pub solid: bool,
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+}
+
+impl TileManifest {
+    pub fn load_all() -> anyhow::Result<Vec<Self>> {
+        let manifests = util::load_manifest::<Self>(TILES_MANIFEST)?;
+
+        for (i, manifest) in manifests.iter().enumerate() {
+            manifest.validate()?;
+
+            if manifests[..i].iter().any(|m| m.id == manifest.id) {
+                anyhow::bail!("Tile \"{}\" is defined more than once", manifest.id);
+            }
+        }
+
+        Ok(manifests)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let Self {
+            id,
+            min,
+            max,
+            rand,
+            deposit,
+            ..
+        } = self;
+
+        if !(0.0..=1.0).contains(rand) {
+            anyhow::bail!("Tile \"{id}\" has a rand value of {rand}, which is not within 0..=1");
+        }
+
+        if *deposit == Some(0) {
+            anyhow::bail!("Tile \"{id}\" has an empty deposit");
+        }
+
+        if min > max {
+            anyhow::bail!("Tile \"{id}\" has a min value of {min} above its max value of {max}");
+        }
+
+        Ok(())
+    }
+
+    pub fn color(&self) -> anyhow::Result<[u8; 3]> {
+        if let Some(color) = self.color {
+            return Ok(color);
+        }
+
+        let path = util::asset_texture_path(&self.texture);
+        let img = image::open(&path)
+            .with_context(|| format!("Failed to read texture {}", path.display()))?
+            .to_rgba8();
+        let mut sum = [0u64; 3];
+        let mut weight = 0;
+
+        for p in img.pixels() {
+            let a = p[3] as u64;
+
+            for (s, c) in sum.iter_mut().zip(p.0) {
+                *s += c as u64 * a;
+            }
+
+            weight += a;
+        }
+
+        Ok(sum.map(|s| s.checked_div(weight).unwrap_or_default() as u8))
+    }
+}
+
+fn default_solid() -> bool {
+    true
+}
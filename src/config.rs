@@ -0,0 +1,43 @@
+use crate::util;
+use hex::anyhow::{self, Context};
+use serde_derive::{Deserialize, Serialize};
+use std::{fs, path::Path, time::Duration};
+
+pub const CONFIG_PATH: &str = "config.json";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub autosave_interval: u64,
+    pub backups: usize,
+}
+
+impl Config {
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Path::new(CONFIG_PATH);
+
+        if Path::exists(path) {
+            serde_json::from_str(&fs::read_to_string(path)?)
+                .with_context(|| format!("Invalid config {}", path.display()))
+        } else {
+            let config = Self::default();
+
+            util::write_atomic(path, serde_json::to_string_pretty(&config)?.as_bytes())?;
+
+            Ok(config)
+        }
+    }
+
+    pub fn autosave_interval(&self) -> Duration {
+        Duration::from_secs(self.autosave_interval)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            autosave_interval: 300,
+            backups: 3,
+        }
+    }
+}
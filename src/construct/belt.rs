@@ -0,0 +1,153 @@
+use hex::ecs::Id;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+pub const BELT_SPEED: f32 = 0.015;
+pub const BELT_SPACING: f32 = 0.25;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BeltItem {
+    pub id: String,
+    pub progress: f32,
+    #[serde(skip)]
+    pub entity: Option<Id>,
+}
+
+impl BeltItem {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            progress: 0.0,
+            entity: None,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Belt {
+    pub items: VecDeque<BeltItem>,
+}
+
+impl Belt {
+    pub fn restore(&self) -> Self {
+        Self {
+            items: self
+                .items
+                .iter()
+                .map(|i| BeltItem {
+                    entity: None,
+                    ..i.clone()
+                })
+                .collect(),
+        }
+    }
+
+    pub fn has_room(&self) -> bool {
+        self.items
+            .back()
+            .map(|i| i.progress >= BELT_SPACING)
+            .unwrap_or(true)
+    }
+
+    pub fn push(&mut self, item: BeltItem) -> bool {
+        if self.has_room() {
+            self.items.push_back(BeltItem {
+                progress: 0.0,
+                ..item
+            });
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn advance(&mut self) {
+        let mut limit = 1.0;
+
+        for item in &mut self.items {
+            item.progress = (item.progress + BELT_SPEED).min(limit).max(item.progress);
+            limit = item.progress - BELT_SPACING;
+        }
+    }
+
+    pub fn ready(&self) -> Option<&BeltItem> {
+        self.items.front().filter(|i| i.progress >= 1.0)
+    }
+
+    pub fn describe(&self) -> String {
+        format!("Belt: {} items", self.items.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::anyhow;
+
+    fn run(belt: &mut Belt, ticks: usize) {
+        for _ in 0..ticks {
+            belt.advance();
+        }
+    }
+
+    #[test]
+    fn push_keeps_items_spaced() {
+        let mut belt = Belt::default();
+
+        assert!(belt.push(BeltItem::new("metal")));
+        assert!(!belt.push(BeltItem::new("ice")));
+
+        run(&mut belt, (BELT_SPACING / BELT_SPEED).ceil() as usize);
+
+        assert!(belt.push(BeltItem::new("ice")));
+        assert_eq!(belt.items.len(), 2);
+        assert_eq!(belt.items[1].progress, 0.0);
+    }
+
+    #[test]
+    fn advance_queues_items_behind_the_front() {
+        let mut belt = Belt::default();
+
+        belt.push(BeltItem::new("metal"));
+        run(&mut belt, (BELT_SPACING / BELT_SPEED).ceil() as usize);
+        belt.push(BeltItem::new("ice"));
+        run(&mut belt, (2.0 / BELT_SPEED) as usize);
+
+        assert_eq!(belt.ready().map(|i| i.id.as_str()), Some("metal"));
+        assert_eq!(belt.items[1].progress, 1.0 - BELT_SPACING);
+
+        belt.items.pop_front();
+        run(&mut belt, 1);
+
+        assert!(belt.ready().is_none());
+
+        run(&mut belt, (BELT_SPACING / BELT_SPEED).ceil() as usize);
+
+        assert_eq!(belt.ready().map(|i| i.id.as_str()), Some("ice"));
+    }
+
+    #[test]
+    fn restore_keeps_queue_across_save_and_reload() -> anyhow::Result<()> {
+        let mut belt = Belt::default();
+
+        belt.push(BeltItem::new("metal"));
+        run(&mut belt, 20);
+        belt.push(BeltItem::new("ice"));
+        run(&mut belt, 5);
+        belt.items[0].entity = Some(1);
+
+        let saved: Belt = serde_json::from_str(&serde_json::to_string(&belt)?)?;
+        let restored = saved.restore();
+
+        assert_eq!(restored.items.len(), 2);
+
+        for (restored, item) in restored.items.iter().zip(&belt.items) {
+            assert_eq!(restored.id, item.id);
+            assert_eq!(restored.progress, item.progress);
+            assert!(restored.entity.is_none());
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use super::{Belt, Crafter, Inventory, Power, Storage};
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -7,4 +8,28 @@ pub struct ConstructData {
     pub rotation: f32,
     pub mode: Option<bool>,
     pub tick_amount: u32,
+    pub crafter: Option<Crafter>,
+    pub inventory: Option<Inventory>,
+    pub belt: Option<Belt>,
+    pub storage: Option<Storage>,
+    pub power: Option<Power>,
+    pub filter: Option<Vec<String>>,
+}
+
+impl ConstructData {
+    pub fn new(id: String, position: [f32; 2], rotation: f32) -> Self {
+        Self {
+            id,
+            position,
+            rotation,
+            mode: None,
+            tick_amount: 0,
+            crafter: None,
+            inventory: None,
+            belt: None,
+            storage: None,
+            power: None,
+            filter: None,
+        }
+    }
 }
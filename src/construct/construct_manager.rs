@@ -1,23 +1,126 @@
-use super::Construct;
+use super::{Construct, Grid, Inventory, Power, PowerKind};
+use crate::{spatial::SpatialIndex, tag::Tag};
 use hex::{
     anyhow,
-    ecs::{ev::Control, system_manager::System, ComponentManager, Context, EntityManager, Ev},
+    components::Transform,
+    ecs::{ev::Control, system_manager::System, ComponentManager, Context, EntityManager, Ev, Id},
     glium::glutin::event::Event,
 };
-use std::time::{Duration, Instant};
+use std::{
+    cell::OnceCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 pub const TICK_INTERVAL: Duration = Duration::from_millis(10);
 
 pub struct ConstructManager {
     last_tick: Instant,
+    index: OnceCell<Option<Id>>,
 }
 
 impl Default for ConstructManager {
     fn default() -> Self {
         Self {
             last_tick: Instant::now(),
+            index: OnceCell::new(),
+        }
+    }
+}
+
+impl ConstructManager {
+    fn root(grids: &mut HashMap<Id, Id>, e: Id) -> Id {
+        let parent = grids.get(&e).cloned().unwrap_or(e);
+
+        if parent == e {
+            return e;
+        }
+
+        let root = Self::root(grids, parent);
+
+        grids.insert(e, root);
+
+        root
+    }
+
+    fn balance(index: Id, (em, cm): (&mut EntityManager, &mut ComponentManager)) {
+        let powered: Vec<_> = em
+            .entities()
+            .filter_map(|e| {
+                let power = cm.get::<Construct>(e)?.power.as_ref()?;
+                let range = match power.kind {
+                    PowerKind::Pole { range } => Some(range),
+                    _ => None,
+                };
+
+                Some((e, cm.get::<Transform>(e)?.position(), range))
+            })
+            .collect();
+        let links: Vec<_> = powered
+            .iter()
+            .filter_map(|(e, position, range)| Some((*e, *position, (*range)?)))
+            .flat_map(|(e, position, range)| {
+                cm.get::<SpatialIndex>(index)
+                    .map(|i| i.radius(position, range))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |other| (e, other))
+            })
+            .collect();
+        let grids = {
+            let members: Vec<_> = powered
+                .iter()
+                .filter_map(|(e, _, _)| {
+                    let c = cm.get::<Construct>(*e)?;
+
+                    Some((*e, c.power.as_ref()?, c.inventory.as_ref()))
+                })
+                .collect();
+
+            Self::grids(&members, &links)
+        };
+
+        for (e, grid) in grids {
+            if let Some(Construct {
+                power: Some(power),
+                inventory,
+                ..
+            }) = cm.get_mut::<Construct>(e)
+            {
+                power.supply(grid, inventory.as_mut());
+            }
         }
     }
+
+    fn grids(
+        members: &[(Id, &Power, Option<&Inventory>)],
+        links: &[(Id, Id)],
+    ) -> HashMap<Id, Grid> {
+        let mut roots: HashMap<Id, Id> = members.iter().map(|(e, _, _)| (*e, *e)).collect();
+
+        for (a, b) in links {
+            if roots.contains_key(a) && roots.contains_key(b) {
+                let (a, b) = (Self::root(&mut roots, *a), Self::root(&mut roots, *b));
+
+                roots.insert(a, b);
+            }
+        }
+
+        let mut totals: HashMap<Id, Grid> = HashMap::new();
+
+        for (e, power, inventory) in members {
+            let total = totals.entry(Self::root(&mut roots, *e)).or_default();
+
+            total.supply += power.available(*inventory);
+            total.demand += power.draw();
+            total.connected |= !matches!(power.kind, PowerKind::Consumer { .. });
+        }
+
+        members
+            .iter()
+            .map(|(e, _, _)| (*e, totals[&Self::root(&mut roots, *e)]))
+            .collect()
+    }
 }
 
 impl System for ConstructManager {
@@ -37,10 +140,21 @@ impl System for ConstructManager {
             if now.duration_since(self.last_tick) >= TICK_INTERVAL {
                 self.last_tick = now;
 
+                if let Some(index) = *self
+                    .index
+                    .get_or_init(|| Tag::new("spatial_index").find((em, cm)))
+                {
+                    Self::balance(index, (em, cm));
+                }
+
                 let entities: Vec<_> = em.entities().collect();
 
                 for e in entities {
                     if let Some(update) = cm.get_mut::<Construct>(e).and_then(|c| {
+                        if !c.powered() {
+                            return None;
+                        }
+
                         c.tick_amount += 1;
 
                         (c.tick_amount >= c.update_tick).then(|| {
@@ -58,3 +172,97 @@ impl System for ConstructManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solar(supply: f32) -> Power {
+        Power::new(PowerKind::Generator {
+            supply,
+            fuel: None,
+            burn_ticks: 0,
+        })
+    }
+
+    fn consumer(draw: f32) -> Power {
+        Power::new(PowerKind::Consumer { draw })
+    }
+
+    fn pole() -> Power {
+        Power::new(PowerKind::Pole { range: 5.0 })
+    }
+
+    #[test]
+    fn grids_slow_consumers_on_short_supply() {
+        let (generator, pole, a, b) = (solar(15.0), pole(), consumer(10.0), consumer(20.0));
+        let grids = ConstructManager::grids(
+            &[
+                (0, &generator, None),
+                (1, &pole, None),
+                (2, &a, None),
+                (3, &b, None),
+            ],
+            &[(1, 0), (1, 2), (1, 3)],
+        );
+
+        for e in 0..4 {
+            assert_eq!(
+                grids[&e],
+                Grid {
+                    supply: 15.0,
+                    demand: 30.0,
+                    connected: true
+                }
+            );
+        }
+
+        assert_eq!(grids[&2].satisfaction(), 0.5);
+        assert_eq!(grids[&0].load(), 1.0);
+    }
+
+    #[test]
+    fn grids_stop_consumers_without_supply() {
+        let (pole, a) = (pole(), consumer(10.0));
+        let grids = ConstructManager::grids(&[(0, &pole, None), (1, &a, None)], &[(0, 1)]);
+
+        assert!(grids[&1].connected);
+        assert_eq!(grids[&1].satisfaction(), 0.0);
+    }
+
+    #[test]
+    fn grids_stop_unconnected_consumers() {
+        let (generator, a) = (solar(100.0), consumer(10.0));
+        let grids = ConstructManager::grids(&[(0, &generator, None), (1, &a, None)], &[]);
+
+        assert!(!grids[&1].connected);
+        assert_eq!(grids[&1].satisfaction(), 0.0);
+        assert_eq!(grids[&0].load(), 0.0);
+    }
+
+    #[test]
+    fn supply_keeps_legacy_consumers_running_until_connected() {
+        let mut power = consumer(10.0);
+
+        power.legacy = true;
+        power.supply(Grid::default(), None);
+
+        assert_eq!(power.satisfaction, 1.0);
+
+        power.supply(
+            Grid {
+                supply: 5.0,
+                demand: 10.0,
+                connected: true,
+            },
+            None,
+        );
+
+        assert!(!power.legacy);
+        assert_eq!(power.satisfaction, 0.5);
+
+        power.supply(Grid::default(), None);
+
+        assert_eq!(power.satisfaction, 0.0);
+    }
+}
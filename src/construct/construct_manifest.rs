@@ -0,0 +1,107 @@
+use super::{power::PowerKind, Power};
+use serde_derive::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ConstructManifest {
+    pub id: String,
+    pub texture: String,
+    #[serde(default = "default_update_tick")]
+    pub update_tick: u32,
+    #[serde(default)]
+    pub inventory: Option<InventoryManifest>,
+    #[serde(default)]
+    pub power_draw: Option<f32>,
+    #[serde(flatten)]
+    pub kind: ConstructKind,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct InventoryManifest {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub capacity: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConstructKind {
+    Miner,
+    Router {
+        dir: f32,
+    },
+    Sorter {
+        dir: f32,
+        #[serde(default)]
+        filter: Vec<String>,
+    },
+    Splitter {
+        dir: f32,
+    },
+    Furnace,
+    Assembler,
+    Conveyor,
+    Storage {
+        capacity: u32,
+    },
+    Generator {
+        supply: f32,
+        #[serde(default)]
+        fuel: Option<String>,
+        #[serde(default = "default_burn_ticks")]
+        burn_ticks: u32,
+    },
+    Pole {
+        range: f32,
+    },
+}
+
+impl ConstructKind {
+    pub fn inventory(&self) -> Option<InventoryManifest> {
+        let (inputs, outputs, capacity) = match self {
+            Self::Miner => (0, 1, 8),
+            Self::Furnace => (1, 1, 10),
+            Self::Assembler => (3, 1, 10),
+            Self::Generator { fuel: Some(_), .. } => (1, 0, 10),
+            _ => return None,
+        };
+
+        Some(InventoryManifest {
+            inputs,
+            outputs,
+            capacity,
+        })
+    }
+
+    pub fn power(&self, draw: Option<f32>) -> Option<Power> {
+        let kind = match self {
+            Self::Generator {
+                supply,
+                fuel,
+                burn_ticks,
+            } => PowerKind::Generator {
+                supply: *supply,
+                fuel: fuel.clone(),
+                burn_ticks: (*burn_ticks).max(1),
+            },
+            Self::Pole { range } => PowerKind::Pole { range: *range },
+            _ => PowerKind::Consumer {
+                draw: draw.or(match self {
+                    Self::Miner => Some(5.0),
+                    Self::Furnace => Some(10.0),
+                    Self::Assembler => Some(20.0),
+                    _ => None,
+                })?,
+            },
+        };
+
+        Some(Power::new(kind))
+    }
+}
+
+fn default_update_tick() -> u32 {
+    1
+}
+
+fn default_burn_ticks() -> u32 {
+    1000
+}
@@ -0,0 +1,196 @@
+use super::{Inventory, Recipe};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Crafter {
+    pub recipe: Option<String>,
+    pub progress: Option<(String, u32)>,
+}
+
+impl Crafter {
+    fn selected<'a>(&'a self, recipes: &'a [Recipe]) -> impl Iterator<Item = &'a Recipe> {
+        recipes
+            .iter()
+            .filter(|r| self.recipe.as_ref().map(|id| *id == r.id).unwrap_or(true))
+    }
+
+    pub fn accepts(&self, recipes: &[Recipe], item: &str) -> bool {
+        self.selected(recipes).any(|r| r.input(item).is_some())
+    }
+
+    pub fn tick(&mut self, recipes: &[Recipe], inventory: &mut Inventory) {
+        inventory.reject(|item| self.accepts(recipes, item));
+
+        match self.progress.take() {
+            Some((id, ticks)) => {
+                let Some(recipe) = recipes.iter().find(|r| r.id == id) else {
+                    return;
+                };
+
+                if ticks + 1 < recipe.ticks {
+                    self.progress = Some((id, ticks + 1));
+                } else if inventory.fits(&recipe.outputs) {
+                    inventory.push(&recipe.outputs);
+                } else {
+                    self.progress = Some((id, ticks));
+                }
+            }
+            None => {
+                let recipe = self
+                    .selected(recipes)
+                    .find(|r| inventory.has(&r.inputs) && inventory.fits(&r.outputs))
+                    .cloned();
+
+                if let Some(recipe) = recipe {
+                    inventory.take(&recipe.inputs);
+
+                    self.progress = Some((recipe.id, 0));
+                }
+            }
+        }
+    }
+
+    pub fn cycle(&mut self, recipes: &[Recipe]) {
+        let next = match &self.recipe {
+            Some(id) => recipes
+                .iter()
+                .position(|r| r.id == *id)
+                .and_then(|i| recipes.get(i + 1)),
+            None => recipes.first(),
+        };
+
+        self.recipe = next.map(|r| r.id.clone());
+    }
+
+    pub fn describe(&self, recipes: &HashMap<String, Recipe>) -> String {
+        let mut text = format!("Recipe: {}", self.recipe.as_deref().unwrap_or("any"));
+
+        if let Some((id, ticks)) = &self.progress {
+            if let Some(recipe) = recipes.get(id) {
+                text += &format!(" | Crafting {id} {}%", (ticks + 1) * 100 / recipe.ticks);
+            }
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construct::{construct_manifest::InventoryManifest, recipe::RecipeStack};
+
+    fn recipe(id: &str, inputs: &[(&str, u32)], output: &str, ticks: u32) -> Recipe {
+        Recipe {
+            id: id.to_string(),
+            inputs: inputs
+                .iter()
+                .map(|(i, c)| RecipeStack::new(i, *c))
+                .collect(),
+            outputs: vec![RecipeStack::new(output, 1)],
+            ticks,
+            machines: vec!["assembler".to_string()],
+        }
+    }
+
+    fn inventory(capacity: u32) -> Inventory {
+        Inventory::new(&InventoryManifest {
+            inputs: 2,
+            outputs: 1,
+            capacity,
+        })
+    }
+
+    #[test]
+    fn tick_consumes_inputs_and_crafts_after_recipe_ticks() {
+        let recipes = [recipe(
+            "plate",
+            &[("refined_metal", 2), ("metal", 1)],
+            "plate",
+            3,
+        )];
+        let mut crafter = Crafter::default();
+        let mut inventory = inventory(5);
+
+        for item in ["refined_metal", "refined_metal", "refined_metal", "metal"] {
+            inventory.insert(item);
+        }
+
+        crafter.tick(&recipes, &mut inventory);
+
+        assert_eq!(crafter.progress, Some(("plate".to_string(), 0)));
+        assert_eq!(inventory.count("refined_metal"), 1);
+        assert_eq!(inventory.count("metal"), 0);
+
+        for _ in 0..2 {
+            crafter.tick(&recipes, &mut inventory);
+        }
+
+        assert_eq!(inventory.peek(), None);
+
+        crafter.tick(&recipes, &mut inventory);
+
+        assert!(crafter.progress.is_none());
+        assert_eq!(inventory.peek(), Some("plate"));
+
+        crafter.tick(&recipes, &mut inventory);
+
+        assert!(crafter.progress.is_none());
+        assert_eq!(inventory.count("refined_metal"), 1);
+    }
+
+    #[test]
+    fn tick_holds_progress_while_outputs_are_full() {
+        let recipes = [recipe("refined_metal", &[("metal", 1)], "refined_metal", 2)];
+        let mut crafter = Crafter::default();
+        let mut inventory = inventory(1);
+
+        inventory.insert("metal");
+        crafter.tick(&recipes, &mut inventory);
+        inventory.push(&recipes[0].outputs);
+
+        for _ in 0..3 {
+            crafter.tick(&recipes, &mut inventory);
+        }
+
+        assert_eq!(crafter.progress, Some(("refined_metal".to_string(), 1)));
+
+        inventory.insert("metal");
+        inventory.pop();
+        crafter.tick(&recipes, &mut inventory);
+
+        assert!(crafter.progress.is_none());
+        assert_eq!(inventory.peek(), Some("refined_metal"));
+
+        crafter.tick(&recipes, &mut inventory);
+
+        assert_eq!(inventory.count("metal"), 1);
+        assert!(crafter.progress.is_none());
+    }
+
+    #[test]
+    fn cycle_narrows_accepted_inputs() {
+        let recipes = [
+            recipe("plate", &[("refined_metal", 2)], "plate", 1),
+            recipe("refined_metal", &[("metal", 1)], "refined_metal", 1),
+        ];
+        let mut crafter = Crafter::default();
+
+        assert!(crafter.accepts(&recipes, "metal"));
+        assert!(crafter.accepts(&recipes, "refined_metal"));
+
+        crafter.cycle(&recipes);
+
+        assert_eq!(crafter.recipe.as_deref(), Some("plate"));
+        assert!(!crafter.accepts(&recipes, "metal"));
+
+        crafter.cycle(&recipes);
+
+        assert_eq!(crafter.recipe.as_deref(), Some("refined_metal"));
+
+        crafter.cycle(&recipes);
+
+        assert!(crafter.recipe.is_none());
+    }
+}
@@ -0,0 +1,26 @@
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Grid {
+    pub supply: f32,
+    pub demand: f32,
+    pub connected: bool,
+}
+
+impl Grid {
+    pub fn satisfaction(&self) -> f32 {
+        if !self.connected {
+            0.0
+        } else if self.demand > 0.0 {
+            (self.supply / self.demand).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn load(&self) -> f32 {
+        if self.supply > 0.0 {
+            (self.demand / self.supply).min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use super::{construct_manifest::InventoryManifest, recipe::RecipeStack};
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Slot {
+    pub item: Option<String>,
+    pub count: u32,
+    pub capacity: u32,
+}
+
+impl Slot {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            capacity,
+            ..Default::default()
+        }
+    }
+
+    pub fn space(&self, item: &str) -> u32 {
+        match &self.item {
+            Some(i) if i != item => 0,
+            _ => self.capacity.saturating_sub(self.count),
+        }
+    }
+
+    pub fn add(&mut self, item: &str, count: u32) {
+        self.item = Some(item.to_string());
+        self.count += count;
+    }
+
+    pub fn remove(&mut self, count: u32) {
+        self.count = self.count.saturating_sub(count);
+
+        if self.count == 0 {
+            self.item = None;
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Inventory {
+    pub inputs: Vec<Slot>,
+    pub outputs: Vec<Slot>,
+}
+
+impl Inventory {
+    pub fn new(
+        InventoryManifest {
+            inputs,
+            outputs,
+            capacity,
+        }: &InventoryManifest,
+    ) -> Self {
+        Self {
+            inputs: vec![Slot::new(*capacity); *inputs],
+            outputs: vec![Slot::new(*capacity); *outputs],
+        }
+    }
+
+    pub fn restore(&mut self, saved: &Inventory) {
+        for (slots, saved) in [
+            (&mut self.inputs, &saved.inputs),
+            (&mut self.outputs, &saved.outputs),
+        ] {
+            for (slot, saved) in slots.iter_mut().zip(saved) {
+                slot.item = saved.item.clone();
+                slot.count = saved.count;
+            }
+        }
+    }
+
+    fn slot<'a>(slots: &'a mut [Slot], item: &str) -> Option<&'a mut Slot> {
+        if slots.iter().any(|s| s.item.as_deref() == Some(item)) {
+            slots.iter_mut().find(|s| s.item.as_deref() == Some(item))
+        } else {
+            slots.iter_mut().find(|s| s.item.is_none())
+        }
+    }
+
+    pub fn insert(&mut self, item: &str) -> bool {
+        match Self::slot(&mut self.inputs, item).filter(|s| s.space(item) > 0) {
+            Some(slot) => {
+                slot.add(item, 1);
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn count(&self, item: &str) -> u32 {
+        self.inputs
+            .iter()
+            .filter(|s| s.item.as_deref() == Some(item))
+            .map(|s| s.count)
+            .sum()
+    }
+
+    pub fn has(&self, stacks: &[RecipeStack]) -> bool {
+        stacks.iter().all(|s| self.count(&s.item) >= s.count)
+    }
+
+    pub fn take(&mut self, stacks: &[RecipeStack]) {
+        for stack in stacks {
+            if let Some(slot) = self
+                .inputs
+                .iter_mut()
+                .find(|s| s.item.as_deref() == Some(stack.item.as_str()))
+            {
+                slot.remove(stack.count);
+            }
+        }
+    }
+
+    pub fn fits(&self, stacks: &[RecipeStack]) -> bool {
+        let mut outputs = self.outputs.clone();
+
+        stacks
+            .iter()
+            .all(|s| match Self::slot(&mut outputs, &s.item) {
+                Some(slot) if slot.space(&s.item) >= s.count => {
+                    slot.add(&s.item, s.count);
+
+                    true
+                }
+                _ => false,
+            })
+    }
+
+    pub fn push(&mut self, stacks: &[RecipeStack]) {
+        for stack in stacks {
+            if let Some(slot) = Self::slot(&mut self.outputs, &stack.item) {
+                slot.add(&stack.item, stack.count);
+            }
+        }
+    }
+
+    pub fn reject<F: Fn(&str) -> bool>(&mut self, keep: F) {
+        for input in &mut self.inputs {
+            let Some(item) = input.item.clone().filter(|i| !keep(i)) else {
+                continue;
+            };
+
+            if let Some(slot) = Self::slot(&mut self.outputs, &item) {
+                let count = slot.space(&item).min(input.count);
+
+                if count > 0 {
+                    slot.add(&item, count);
+                    input.remove(count);
+                }
+            }
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        let slot = self.outputs.iter_mut().find(|s| s.count > 0)?;
+        let item = slot.item.clone();
+
+        slot.remove(1);
+
+        item
+    }
+
+    pub fn peek(&self) -> Option<&str> {
+        self.outputs
+            .iter()
+            .find(|s| s.count > 0)
+            .and_then(|s| s.item.as_deref())
+    }
+
+    pub fn describe(&self) -> String {
+        let slots = |slots: &[Slot]| {
+            let slots: Vec<_> = slots
+                .iter()
+                .map(|s| match &s.item {
+                    Some(item) => format!("{item} {}/{}", s.count, s.capacity),
+                    None => format!("empty 0/{}", s.capacity),
+                })
+                .collect();
+
+            slots.join(", ")
+        };
+        let mut text = Vec::new();
+
+        if !self.inputs.is_empty() {
+            text.push(format!("In: {}", slots(&self.inputs)));
+        }
+
+        if !self.outputs.is_empty() {
+            text.push(format!("Out: {}", slots(&self.outputs)));
+        }
+
+        text.join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::anyhow;
+
+    fn inventory(inputs: usize, outputs: usize, capacity: u32) -> Inventory {
+        Inventory::new(&InventoryManifest {
+            inputs,
+            outputs,
+            capacity,
+        })
+    }
+
+    #[test]
+    fn insert_stops_at_capacity() {
+        let mut inventory = inventory(1, 1, 2);
+
+        assert!(inventory.insert("metal"));
+        assert!(inventory.insert("metal"));
+        assert!(!inventory.insert("metal"));
+        assert!(!inventory.insert("ice"));
+        assert_eq!(inventory.count("metal"), 2);
+    }
+
+    #[test]
+    fn insert_fills_a_free_slot_per_item() {
+        let mut inventory = inventory(2, 1, 1);
+
+        assert!(inventory.insert("metal"));
+        assert!(inventory.insert("ice"));
+        assert!(!inventory.insert("metal"));
+        assert_eq!(inventory.count("ice"), 1);
+    }
+
+    #[test]
+    fn take_consumes_only_what_it_has() {
+        let mut inventory = inventory(2, 1, 5);
+        let stacks = [RecipeStack::new("metal", 2), RecipeStack::new("ice", 1)];
+
+        for _ in 0..3 {
+            inventory.insert("metal");
+        }
+
+        assert!(!inventory.has(&stacks));
+
+        inventory.insert("ice");
+
+        assert!(inventory.has(&stacks));
+
+        inventory.take(&stacks);
+
+        assert_eq!(inventory.count("metal"), 1);
+        assert_eq!(inventory.count("ice"), 0);
+        assert!(inventory.inputs[1].item.is_none());
+    }
+
+    #[test]
+    fn fits_holds_back_full_outputs() {
+        let mut inventory = inventory(1, 1, 3);
+        let stacks = [RecipeStack::new("plate", 2)];
+
+        assert!(inventory.fits(&stacks));
+
+        inventory.push(&stacks);
+
+        assert!(!inventory.fits(&stacks));
+        assert!(!inventory.fits(&[RecipeStack::new("metal", 1)]));
+        assert!(inventory.fits(&[RecipeStack::new("plate", 1)]));
+    }
+
+    #[test]
+    fn pop_drains_outputs_in_slot_order() {
+        let mut inventory = inventory(0, 2, 2);
+
+        inventory.push(&[RecipeStack::new("plate", 1), RecipeStack::new("metal", 1)]);
+
+        assert_eq!(inventory.peek(), Some("plate"));
+        assert_eq!(inventory.pop().as_deref(), Some("plate"));
+        assert_eq!(inventory.pop().as_deref(), Some("metal"));
+        assert_eq!(inventory.pop(), None);
+        assert_eq!(inventory.peek(), None);
+    }
+
+    #[test]
+    fn reject_moves_unused_inputs_while_outputs_have_room() {
+        let mut inventory = inventory(2, 1, 2);
+
+        inventory.insert("metal");
+        inventory.insert("ice");
+        inventory.insert("ice");
+        inventory.push(&[RecipeStack::new("ice", 1)]);
+        inventory.reject(|item| item == "metal");
+
+        assert_eq!(inventory.count("metal"), 1);
+        assert_eq!(inventory.count("ice"), 1);
+        assert_eq!(inventory.outputs[0].count, 2);
+
+        inventory.pop();
+        inventory.pop();
+        inventory.reject(|item| item == "metal");
+
+        assert_eq!(inventory.count("ice"), 0);
+        assert_eq!(inventory.peek(), Some("ice"));
+    }
+
+    #[test]
+    fn restore_keeps_manifest_capacity() -> anyhow::Result<()> {
+        let mut saved = inventory(1, 1, 5);
+
+        saved.insert("metal");
+        saved.push(&[RecipeStack::new("plate", 1)]);
+
+        let saved: Inventory = serde_json::from_str(&serde_json::to_string(&saved)?)?;
+        let mut inventory = inventory(1, 1, 2);
+
+        inventory.restore(&saved);
+
+        assert_eq!(inventory.count("metal"), 1);
+        assert_eq!(inventory.peek(), Some("plate"));
+        assert_eq!(inventory.inputs[0].capacity, 2);
+
+        Ok(())
+    }
+}
@@ -1,50 +1,28 @@
+use super::item_manifest::ItemManifest;
 use crate::util;
 use hex::{
-    anyhow,
+    anyhow::{self, Context as _},
     ecs::{component_manager::Component, Context, Id},
 };
 use hex_instance::Instance;
 
-pub const REFINED_METAL: &str = "refined_metal";
-pub const METAL: &str = "metal";
-
 #[derive(Clone)]
 pub struct Item {
     pub id: String,
     pub last: Option<Id>,
-    pub refined: Option<String>,
 }
 
 impl Item {
-    pub fn metal(context: &Context) -> anyhow::Result<(Self, Instance)> {
-        Ok((
-            Self {
-                id: METAL.to_string(),
-                last: None,
-                refined: Some(REFINED_METAL.to_string()),
-            },
-            Instance::new(
-                util::load_texture(&context.display, include_bytes!("metal.png"))?,
-                [1.0; 4],
-                -3.5,
-                true,
-            ),
-        ))
-    }
+    pub fn load(
+        context: &Context,
+        ItemManifest { id, texture }: ItemManifest,
+    ) -> anyhow::Result<(Self, Instance)> {
+        let texture = util::load_asset_texture(&context.display, &texture)
+            .with_context(|| format!("Failed to load item \"{id}\""))?;
 
-    pub fn refined_metal(context: &Context) -> anyhow::Result<(Self, Instance)> {
         Ok((
-            Self {
-                id: REFINED_METAL.to_string(),
-                last: None,
-                refined: None,
-            },
-            Instance::new(
-                util::load_texture(&context.display, include_bytes!("refined_metal.png"))?,
-                [1.0; 4],
-                -3.5,
-                true,
-            ),
+            Self { id, last: None },
+            Instance::new(texture, [1.0; 4], -3.5, true),
         ))
     }
 }
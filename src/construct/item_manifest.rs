@@ -0,0 +1,7 @@
+use serde_derive::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ItemManifest {
+    pub id: String,
+    pub texture: String,
+}
@@ -1,21 +1,40 @@
+pub mod belt;
 pub mod construct_data;
 pub mod construct_manager;
+pub mod construct_manifest;
+pub mod crafter;
+pub mod grid;
+pub mod inventory;
 pub mod item;
 pub mod item_data;
+pub mod item_manifest;
+pub mod power;
+pub mod recipe;
+pub mod storage;
 
+pub use belt::{Belt, BeltItem};
 pub use construct_data::ConstructData;
 pub use construct_manager::ConstructManager;
+pub use construct_manifest::{ConstructKind, ConstructManifest};
+pub use crafter::Crafter;
+pub use grid::Grid;
+pub use inventory::Inventory;
 pub use item::Item;
 pub use item_data::ItemData;
+pub use item_manifest::ItemManifest;
+pub use power::{Power, PowerKind};
+pub use recipe::{Recipe, RecipeStack};
+pub use storage::Storage;
 
 use crate::{
-    chunk::{Chunk, ChunkManager, Map, CHUNK_SIZE},
+    chunk::{Chunk, ChunkManager, Map},
     player::State,
+    spatial::SpatialIndex,
     tag::Tag,
     util,
 };
 use hex::{
-    anyhow,
+    anyhow::{self, Context as _},
     components::Transform,
     ecs::{
         component_manager::{Component, ComponentManager},
@@ -26,17 +45,19 @@ use hex::{
 };
 use hex_instance::Instance;
 use hex_physics::Physical;
-use std::{f32::consts::PI, rc::Rc};
+use std::{collections::HashMap, f32::consts::PI, iter, rc::Rc};
 
 pub type UpdateFn = dyn Fn(Id, (&mut EntityManager, &mut ComponentManager)) -> anyhow::Result<()>;
+pub type InteractFn = dyn Fn(Id, &mut ComponentManager);
+pub type AcceptFn = dyn Fn(Id, &BeltItem, u32, (&mut EntityManager, &mut ComponentManager)) -> bool;
 
-pub const MINER: &str = "miner";
-pub const RIGHT_ROUTER: &str = "right_router";
-pub const LEFT_ROUTER: &str = "left_router";
-pub const RIGHT_SPLITTER: &str = "right_splitter";
-pub const LEFT_SPLITTER: &str = "left_splitter";
-pub const FURNACE: &str = "furnace";
 pub const PICKUP_BIAS: f32 = 0.1;
+pub const PICKUP_RADIUS: f32 = 1.0;
+pub const EJECT_SPACING: f32 = 0.5;
+pub const MAX_DELIVER_DEPTH: u32 = 8;
+pub const FURNACE: &str = "furnace";
+pub const ASSEMBLER: &str = "assembler";
+pub const MACHINES: &[&str] = &[FURNACE, ASSEMBLER];
 
 #[derive(Clone)]
 pub struct Construct {
@@ -45,127 +66,260 @@ pub struct Construct {
     pub tick_amount: u32,
     pub update_tick: u32,
     pub mode: Option<bool>,
+    pub crafter: Option<Crafter>,
+    pub inventory: Option<Inventory>,
+    pub belt: Option<Belt>,
+    pub storage: Option<Storage>,
+    pub power: Option<Power>,
+    pub filter: Option<Vec<String>>,
+    pub accept: Option<Rc<AcceptFn>>,
+    pub interact: Option<Rc<InteractFn>>,
 }
 
 impl Construct {
-    pub fn miner(
+    pub fn load(
         context: &Context,
+        ConstructManifest {
+            id,
+            texture,
+            update_tick,
+            inventory,
+            power_draw,
+            kind,
+        }: ConstructManifest,
         (em, cm): (&mut EntityManager, &mut ComponentManager),
     ) -> anyhow::Result<Option<(Self, Instance)>> {
-        let texture = util::load_texture(&context.display, include_bytes!("miner.png"))?;
+        let texture = util::load_asset_texture(&context.display, &texture)
+            .with_context(|| format!("Failed to load construct \"{id}\""))?;
+        let index = Tag::new("spatial_index").find((em, cm));
+        let player = Tag::new("player").find((em, cm));
+        let inventory = inventory.or_else(|| kind.inventory());
+        let power = kind.power(power_draw);
+        let construct = match kind {
+            ConstructKind::Miner => index.and_then(|index| Self::miner(id, index, (em, cm))),
+            ConstructKind::Router { dir } => player
+                .zip(index)
+                .map(|objects| Self::router(id, dir, objects)),
+            ConstructKind::Sorter { dir, filter } => player
+                .zip(index)
+                .map(|objects| Self::sorter(id, dir, filter, objects)),
+            ConstructKind::Splitter { dir } => player
+                .zip(index)
+                .map(|objects| Self::splitter(id, dir, objects)),
+            ConstructKind::Conveyor => player.zip(index).map(|objects| Self::conveyor(id, objects)),
+            ConstructKind::Storage { capacity } => player
+                .zip(index)
+                .map(|objects| Self::storage(id, capacity, objects)),
+            ConstructKind::Generator { .. } => index.map(|index| Self::generator(id, index)),
+            ConstructKind::Pole { .. } => Some(Self::pole(id)),
+            ConstructKind::Furnace => index.and_then(|index| Self::furnace(id, index, (em, cm))),
+            ConstructKind::Assembler => {
+                index.and_then(|index| Self::assembler(id, index, (em, cm)))
+            }
+        };
 
-        Ok(Tag::new("map")
+        Ok(construct.map(|construct| {
+            (
+                Self {
+                    update_tick,
+                    inventory: inventory.as_ref().map(Inventory::new),
+                    power,
+                    ..construct
+                },
+                Instance::new(texture, [1.0; 4], -3.0, true),
+            )
+        }))
+    }
+
+    pub fn miner(
+        id: String,
+        index: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> Option<Self> {
+        Tag::new("map")
             .find((em, cm))
             .and_then(|map| Some((map, Tag::new("player").find((em, cm))?)))
-            .map(|(map, player)| {
-                (
-                    Self {
-                        id: MINER.to_string(),
-                        update: Rc::new(move |e, (em, cm)| {
-                            if let Some(transform) = cm.get::<Transform>(e).cloned() {
-                                let pos = ChunkManager::chunk_pos(transform.position());
-
-                                if let Some(id) = if let Some(map) = cm.get_mut::<Map>(map) {
-                                    map.loaded.get(&pos).cloned()
-                                } else {
-                                    None
-                                } {
-                                    if let Some(chunk) = cm.get::<Chunk>(id).cloned() {
-                                        let x = CHUNK_SIZE as usize
-                                            - ((pos.0 * CHUNK_SIZE) as usize
-                                                - transform.position().x().floor() as usize);
-                                        let y = CHUNK_SIZE as usize
-                                            - ((pos.1 * CHUNK_SIZE) as usize
-                                                - transform.position().y().floor() as usize);
-                                        let tile_id =
-                                            &chunk.grid.get(x).and_then(|c| c.get(y)?.clone());
-
-                                        if let Some(state) = cm.get::<State>(player).cloned() {
-                                            if let Some(tile_id) = tile_id {
-                                                if let Some((item, instance)) =
-                                                    state.items.get(tile_id)
-                                                {
-                                                    let entity = em.add();
-
-                                                    cm.add(entity, instance.clone(), em);
-                                                    cm.add(entity, item.clone(), em);
-                                                    cm.add(
-                                                        entity,
-                                                        Transform::new(
-                                                            transform.position(),
-                                                            0.0,
-                                                            Vec2d([1.0; 2]),
-                                                            true,
-                                                        ),
-                                                        em,
-                                                    );
-                                                    cm.add(
-                                                        entity,
-                                                        Physical::new(
-                                                            (Mat3d::rotation(transform.rotation())
-                                                                * (Vec2d::new(0.0, 1.0), 1.0))
-                                                                .0,
-                                                            true,
-                                                        ),
-                                                        em,
-                                                    );
-                                                }
-                                            }
-                                        }
+            .map(|(map, player)| Self {
+                id,
+                update: Rc::new(move |e, (em, cm)| {
+                    if let Some(transform) = cm.get::<Transform>(e).cloned() {
+                        let pos = ChunkManager::chunk_pos(transform.position());
+
+                        if let Some(id) = cm
+                            .get::<Map>(map)
+                            .and_then(|map| map.loaded.get(&pos).cloned())
+                        {
+                            let (x, y) = ChunkManager::tile_index(transform.position());
+
+                            if let Some(item) = cm
+                                .get::<Chunk>(id)
+                                .and_then(|chunk| chunk.grid.get(x)?.get(y)?.clone())
+                                .filter(|tile_id| {
+                                    cm.get::<State>(player)
+                                        .map(|s| s.items.contains_key(tile_id))
+                                        .unwrap_or_default()
+                                })
+                                .filter(|tile_id| {
+                                    cm.get::<Construct>(e)
+                                        .and_then(|c| c.inventory.as_ref())
+                                        .map(|i| i.fits(&[RecipeStack::new(tile_id, 1)]))
+                                        .unwrap_or_default()
+                                })
+                            {
+                                let depleted = cm
+                                    .get_mut::<Chunk>(id)
+                                    .and_then(|chunk| chunk.mine((x, y)))
+                                    .map(|(_, depleted)| depleted)
+                                    .unwrap_or_default();
+
+                                if let Some(inventory) = cm
+                                    .get_mut::<Construct>(e)
+                                    .and_then(|c| c.inventory.as_mut())
+                                {
+                                    inventory.push(&[RecipeStack::new(&item, 1)]);
+                                }
+
+                                if depleted {
+                                    if let Some((space, texture)) =
+                                        cm.get::<State>(player).and_then(|state| {
+                                            Some((
+                                                state.space.clone(),
+                                                cm.get::<Instance>(id)?.texture.clone(),
+                                            ))
+                                        })
+                                    {
+                                        ChunkManager::blit_tile(&space, &texture, (x, y));
                                     }
+
+                                    ChunkManager::build_colliders(id, player, (em, cm));
                                 }
                             }
+                        }
+                    }
 
-                            Ok(())
-                        }),
-                        tick_amount: 0,
-                        update_tick: 1000,
-                        mode: None,
-                    },
-                    Instance::new(texture, [1.0; 4], -3.0, true),
+                    Self::unload(e, (player, index), (em, cm));
+
+                    Ok(())
+                }),
+                tick_amount: 0,
+                update_tick: 1000,
+                mode: None,
+                crafter: None,
+                inventory: None,
+                belt: None,
+                storage: None,
+                power: None,
+                filter: None,
+                accept: None,
+                interact: None,
+            })
+    }
+
+    pub fn router(id: String, dir: f32, (player, index): (Id, Id)) -> Self {
+        Self {
+            id,
+            update: Rc::new(move |entity, (em, cm)| Self::route(entity, index, (em, cm), dir)),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: None,
+            crafter: None,
+            inventory: None,
+            belt: None,
+            storage: None,
+            power: None,
+            filter: None,
+            accept: Some(Rc::new(move |entity, item, depth, (em, cm)| {
+                let Some((transform, matches)) = cm
+                    .get::<Transform>(entity)
+                    .cloned()
+                    .zip(cm.get::<Construct>(entity).map(|c| c.matches(&item.id)))
+                else {
+                    return false;
+                };
+                let rotation = if matches {
+                    transform.rotation() + dir * -PI / 2.0
+                } else {
+                    transform.rotation()
+                };
+
+                Self::forward(
+                    entity,
+                    (transform.position(), rotation),
+                    item,
+                    depth,
+                    (player, index),
+                    (em, cm),
                 )
-            }))
+            })),
+            interact: None,
+        }
     }
 
-    pub fn right_router(context: &Context) -> anyhow::Result<(Self, Instance)> {
-        let texture = util::load_texture(&context.display, include_bytes!("right_router.png"))?;
+    pub fn sorter(id: String, dir: f32, filter: Vec<String>, (player, index): (Id, Id)) -> Self {
+        let manifest = filter.clone();
 
-        Ok((
-            Self {
-                id: RIGHT_ROUTER.to_string(),
-                update: Rc::new(move |entity, (em, cm)| Self::router(entity, (em, cm), 1.0)),
-                tick_amount: 0,
-                update_tick: 1,
-                mode: None,
-            },
-            Instance::new(texture, [1.0; 4], -3.0, true),
-        ))
+        Self {
+            filter: Some(filter),
+            interact: Some(Rc::new(move |entity, cm| {
+                let Some(mut items) = cm
+                    .get::<State>(player)
+                    .map(|s| s.items.keys().cloned().collect::<Vec<_>>())
+                else {
+                    return;
+                };
+
+                items.sort();
+
+                if let Some(filter) = cm
+                    .get_mut::<Construct>(entity)
+                    .and_then(|c| c.filter.as_mut())
+                {
+                    *filter = Self::next_filter(filter, &manifest, &items);
+                }
+            })),
+            ..Self::router(id, dir, (player, index))
+        }
     }
 
-    pub fn left_router(context: &Context) -> anyhow::Result<(Self, Instance)> {
-        let texture = util::load_texture(&context.display, include_bytes!("left_router.png"))?;
+    fn next_filter(filter: &[String], manifest: &[String], items: &[String]) -> Vec<String> {
+        let mut states = vec![manifest.to_vec()];
 
-        Ok((
-            Self {
-                id: LEFT_ROUTER.to_string(),
-                update: Rc::new(move |entity, (em, cm)| Self::router(entity, (em, cm), -1.0)),
-                tick_amount: 0,
-                update_tick: 1,
-                mode: None,
-            },
-            Instance::new(texture, [1.0; 4], -3.0, true),
-        ))
+        states.extend(
+            iter::once(Vec::new())
+                .chain(items.iter().map(|i| vec![i.clone()]))
+                .filter(|s| s != manifest),
+        );
+
+        states
+            .iter()
+            .position(|s| s == filter)
+            .and_then(|i| states.get((i + 1) % states.len()))
+            .cloned()
+            .unwrap_or_else(|| manifest.to_vec())
+    }
+
+    pub fn matches(&self, item: &str) -> bool {
+        self.filter
+            .as_ref()
+            .map(|f| f.iter().any(|i| i == item))
+            .unwrap_or(true)
     }
 
-    fn router(
+    fn route(
         entity: Id,
-        (em, cm): (&mut EntityManager, &mut ComponentManager),
+        index: Id,
+        (_, cm): (&mut EntityManager, &mut ComponentManager),
         dir: f32,
     ) -> anyhow::Result<()> {
         if let Some(construct_transform) = cm.get::<Transform>(entity).cloned() {
-            for e in em.entities() {
+            for e in Self::nearby(index, &construct_transform, cm) {
                 if let Some((force, item_position)) = cm.get::<Item>(e).and_then(|item| {
-                    if item.last.map(|l| l != entity).unwrap_or(true) {
+                    if item.last.map(|l| l != entity).unwrap_or(true)
+                        && cm
+                            .get::<Construct>(entity)
+                            .map(|c| c.matches(&item.id))
+                            .unwrap_or_default()
+                    {
                         Some((
                             cm.get::<Physical>(e).map(|p| p.force)?,
                             cm.get::<Transform>(e).map(|t| t.position())?,
@@ -200,43 +354,61 @@ impl Construct {
         Ok(())
     }
 
-    pub fn left_splitter(context: &Context) -> anyhow::Result<(Self, Instance)> {
-        let texture = util::load_texture(&context.display, include_bytes!("left_splitter.png"))?;
-
-        Ok((
-            Self {
-                id: LEFT_SPLITTER.to_string(),
-                update: Rc::new(move |entity, (em, cm)| Self::splitter(entity, (em, cm), -1.0)),
-                tick_amount: 0,
-                update_tick: 1,
-                mode: Some(true),
-            },
-            Instance::new(texture, [1.0; 4], -3.0, true),
-        ))
-    }
+    pub fn splitter(id: String, dir: f32, (player, index): (Id, Id)) -> Self {
+        Self {
+            id,
+            update: Rc::new(move |entity, (em, cm)| Self::split(entity, index, (em, cm), dir)),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: Some(true),
+            crafter: None,
+            inventory: None,
+            belt: None,
+            storage: None,
+            power: None,
+            filter: None,
+            accept: Some(Rc::new(move |entity, item, depth, (em, cm)| {
+                let Some((transform, m)) = cm
+                    .get::<Transform>(entity)
+                    .cloned()
+                    .zip(cm.get::<Construct>(entity).and_then(|c| c.mode))
+                else {
+                    return false;
+                };
+                let rotation = if !m {
+                    transform.rotation() + dir * -PI / 2.0
+                } else {
+                    transform.rotation()
+                };
+                let delivered = Self::forward(
+                    entity,
+                    (transform.position(), rotation),
+                    item,
+                    depth,
+                    (player, index),
+                    (em, cm),
+                );
 
-    pub fn right_splitter(context: &Context) -> anyhow::Result<(Self, Instance)> {
-        let texture = util::load_texture(&context.display, include_bytes!("right_splitter.png"))?;
+                if delivered {
+                    if let Some(c) = cm.get_mut::<Construct>(entity) {
+                        c.mode = Some(!m);
+                    }
+                }
 
-        Ok((
-            Self {
-                id: RIGHT_SPLITTER.to_string(),
-                update: Rc::new(move |entity, (em, cm)| Self::splitter(entity, (em, cm), 1.0)),
-                tick_amount: 0,
-                update_tick: 1,
-                mode: Some(true),
-            },
-            Instance::new(texture, [1.0; 4], -3.0, true),
-        ))
+                delivered
+            })),
+            interact: None,
+        }
     }
 
-    fn splitter(
+    fn split(
         entity: Id,
-        (em, cm): (&mut EntityManager, &mut ComponentManager),
+        index: Id,
+        (_, cm): (&mut EntityManager, &mut ComponentManager),
         dir: f32,
     ) -> anyhow::Result<()> {
         if let Some(construct_transform) = cm.get::<Transform>(entity).cloned() {
-            for e in em.entities() {
+            for e in Self::nearby(index, &construct_transform, cm) {
                 if let Some((force, item_position)) = cm.get::<Item>(e).and_then(|item| {
                     if item.last.map(|l| l != entity).unwrap_or(true) {
                         Some((
@@ -288,64 +460,617 @@ impl Construct {
     }
 
     pub fn furnace(
-        context: &Context,
+        id: String,
+        index: Id,
         (em, cm): (&mut EntityManager, &mut ComponentManager),
-    ) -> anyhow::Result<Option<(Self, Instance)>> {
-        let texture = util::load_texture(&context.display, include_bytes!("furnace.png"))?;
+    ) -> Option<Self> {
+        Tag::new("player").find((em, cm)).map(|player| Self {
+            id,
+            update: Rc::new(move |entity, (em, cm)| {
+                Self::craft(entity, FURNACE, (player, index), (em, cm))
+            }),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: None,
+            crafter: Some(Crafter::default()),
+            inventory: None,
+            belt: None,
+            storage: None,
+            power: None,
+            filter: None,
+            accept: Some(Rc::new(move |entity, item, _, (em, cm)| {
+                Self::absorb(entity, FURNACE, (player, index), item, (em, cm))
+            })),
+            interact: None,
+        })
+    }
 
-        Ok(Tag::new("player").find((em, cm)).map(|player| {
-            (
-                Self {
-                    id: FURNACE.to_string(),
-                    update: Rc::new(move |entity, (em, cm)| {
-                        if let Some(transform) = cm.get::<Transform>(entity).cloned() {
-                            for e in em.entities() {
-                                if let Some((force, position, refined)) =
-                                    cm.get::<Item>(e).and_then(|item| {
-                                        if item.last.map(|l| l != entity).unwrap_or(true) {
-                                            Some((
-                                                cm.get::<Physical>(e).map(|p| p.force)?,
-                                                cm.get::<Transform>(e).map(|t| t.position())?,
-                                                item.refined.clone(),
-                                            ))
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                {
-                                    if Self::pickup(&transform, position, force) {
-                                        if let Some(item_id) = refined {
-                                            if let Some((new_item, new_instance)) =
-                                                if let Some(state) = cm.get::<State>(player) {
-                                                    state.items.get(&item_id).cloned()
-                                                } else {
-                                                    None
-                                                }
-                                            {
-                                                if let Some(item) = cm.get_mut::<Item>(e) {
-                                                    *item = new_item;
-                                                    item.last = Some(entity);
-                                                }
-
-                                                if let Some(instance) = cm.get_mut::<Instance>(e) {
-                                                    *instance = new_instance;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+    pub fn assembler(
+        id: String,
+        index: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> Option<Self> {
+        Tag::new("player").find((em, cm)).map(|player| Self {
+            id,
+            update: Rc::new(move |entity, (em, cm)| {
+                Self::craft(entity, ASSEMBLER, (player, index), (em, cm))
+            }),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: None,
+            crafter: Some(Crafter::default()),
+            inventory: None,
+            belt: None,
+            storage: None,
+            power: None,
+            filter: None,
+            accept: Some(Rc::new(move |entity, item, _, (em, cm)| {
+                Self::absorb(entity, ASSEMBLER, (player, index), item, (em, cm))
+            })),
+            interact: Some(Rc::new(move |entity, cm| {
+                if let Some(recipes) = cm
+                    .get::<State>(player)
+                    .map(|s| Recipe::for_machine(&s.recipes, ASSEMBLER))
+                {
+                    if let Some(crafter) = cm
+                        .get_mut::<Construct>(entity)
+                        .and_then(|c| c.crafter.as_mut())
+                    {
+                        crafter.cycle(&recipes);
+                    }
+                }
+            })),
+        })
+    }
 
-                        Ok(())
-                    }),
-                    tick_amount: 0,
-                    update_tick: 1,
-                    mode: None,
-                },
-                Instance::new(texture, [1.0; 4], -3.0, true),
+    pub fn describe(&self, recipes: &HashMap<String, Recipe>) -> String {
+        let mut text = vec![self.id.clone()];
+
+        if let Some(crafter) = &self.crafter {
+            text.push(crafter.describe(recipes));
+        }
+
+        if let Some(inventory) = &self.inventory {
+            text.push(inventory.describe());
+        }
+
+        if let Some(belt) = &self.belt {
+            text.push(belt.describe());
+        }
+
+        if let Some(storage) = &self.storage {
+            text.push(storage.describe());
+        }
+
+        if let Some(power) = &self.power {
+            text.push(power.describe());
+        }
+
+        if let Some(filter) = &self.filter {
+            text.push(if filter.is_empty() {
+                "Filter: none".to_string()
+            } else {
+                format!("Filter: {}", filter.join(", "))
+            });
+        }
+
+        text.join(" | ")
+    }
+
+    fn craft(
+        entity: Id,
+        machine: &str,
+        (player, index): (Id, Id),
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        let Some((transform, recipes)) = cm.get::<Transform>(entity).cloned().and_then(|t| {
+            Some((
+                t,
+                Recipe::for_machine(&cm.get::<State>(player)?.recipes, machine),
+            ))
+        }) else {
+            return Ok(());
+        };
+
+        for e in Self::nearby(index, &transform, cm) {
+            if let Some((item, force, position)) = cm.get::<Item>(e).and_then(|item| {
+                if item.last.map(|l| l != entity).unwrap_or(true) {
+                    Some((
+                        item.id.clone(),
+                        cm.get::<Physical>(e).map(|p| p.force)?,
+                        cm.get::<Transform>(e).map(|t| t.position())?,
+                    ))
+                } else {
+                    None
+                }
+            }) {
+                if Self::pickup(&transform, position, force) {
+                    Self::absorb(
+                        entity,
+                        machine,
+                        (player, index),
+                        &BeltItem {
+                            entity: Some(e),
+                            ..BeltItem::new(&item)
+                        },
+                        (em, cm),
+                    );
+                }
+            }
+        }
+
+        if let Some(Construct {
+            crafter: Some(crafter),
+            inventory: Some(inventory),
+            ..
+        }) = cm.get_mut::<Construct>(entity)
+        {
+            crafter.tick(&recipes, inventory);
+        }
+
+        Self::unload(entity, (player, index), (em, cm));
+
+        Ok(())
+    }
+
+    fn absorb(
+        entity: Id,
+        machine: &str,
+        (player, index): (Id, Id),
+        item: &BeltItem,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> bool {
+        let Some(recipes) = cm
+            .get::<State>(player)
+            .map(|s| Recipe::for_machine(&s.recipes, machine))
+        else {
+            return false;
+        };
+        let absorbed = cm
+            .get_mut::<Construct>(entity)
+            .filter(|c| {
+                c.crafter
+                    .as_ref()
+                    .map(|cr| cr.accepts(&recipes, &item.id))
+                    .unwrap_or_default()
+            })
+            .and_then(|c| Some(c.inventory.as_mut()?.insert(&item.id)))
+            .unwrap_or_default();
+
+        if absorbed {
+            Self::despawn(item, index, (em, cm));
+        }
+
+        absorbed
+    }
+
+    pub fn conveyor(id: String, (player, index): (Id, Id)) -> Self {
+        Self {
+            id,
+            update: Rc::new(move |entity, (em, cm)| {
+                Self::convey(entity, (player, index), (em, cm))
+            }),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: None,
+            crafter: None,
+            inventory: None,
+            belt: Some(Belt::default()),
+            storage: None,
+            power: None,
+            filter: None,
+            accept: Some(Rc::new(move |entity, item, _, (_, cm)| {
+                cm.get_mut::<Construct>(entity)
+                    .and_then(|c| c.belt.as_mut())
+                    .map(|b| b.push(item.clone()))
+                    .unwrap_or_default()
+            })),
+            interact: None,
+        }
+    }
+
+    fn convey(
+        entity: Id,
+        (player, index): (Id, Id),
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        let Some(transform) = cm.get::<Transform>(entity).cloned() else {
+            return Ok(());
+        };
+        let tile = cm
+            .get::<SpatialIndex>(index)
+            .map(|i| i.tile(SpatialIndex::tile_pos(transform.position())))
+            .unwrap_or_default();
+
+        for e in tile {
+            if let Some(id) = cm
+                .get::<Item>(e)
+                .filter(|i| i.last != Some(entity) && cm.get::<Physical>(e).is_some())
+                .map(|i| i.id.clone())
+            {
+                if cm
+                    .get_mut::<Construct>(entity)
+                    .and_then(|c| c.belt.as_mut())
+                    .map(|b| {
+                        b.push(BeltItem {
+                            entity: Some(e),
+                            ..BeltItem::new(&id)
+                        })
+                    })
+                    .unwrap_or_default()
+                {
+                    cm.rm::<Physical>(e, em);
+
+                    if let Some(index) = cm.get_mut::<SpatialIndex>(index) {
+                        index.remove(e);
+                    }
+                }
+            }
+        }
+
+        let ready = cm
+            .get_mut::<Construct>(entity)
+            .and_then(|c| c.belt.as_mut())
+            .and_then(|b| {
+                b.advance();
+                b.ready().cloned()
+            });
+
+        if let Some(item) = ready {
+            if Self::forward(
+                entity,
+                (transform.position(), transform.rotation()),
+                &item,
+                0,
+                (player, index),
+                (em, cm),
+            ) {
+                if let Some(belt) = cm
+                    .get_mut::<Construct>(entity)
+                    .and_then(|c| c.belt.as_mut())
+                {
+                    belt.items.pop_front();
+                }
+            }
+        }
+
+        Self::draw_belt(entity, &transform, player, (em, cm));
+
+        Ok(())
+    }
+
+    fn draw_belt(
+        entity: Id,
+        transform: &Transform,
+        player: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) {
+        let direction = Self::direction(transform.rotation());
+        let items: Vec<_> = cm
+            .get::<Construct>(entity)
+            .and_then(|c| c.belt.as_ref())
+            .map(|b| b.items.iter().cloned().collect())
+            .unwrap_or_default();
+
+        for (i, item) in items.into_iter().enumerate() {
+            let position = transform.position() + direction * (item.progress - 0.5);
+
+            if let Some(e) = item.entity {
+                if let Some(transform) = cm.get_mut::<Transform>(e) {
+                    transform.set_position(position);
+                }
+            } else if let Some((mut sprite, instance)) = cm
+                .get::<State>(player)
+                .and_then(|s| s.items.get(&item.id).cloned())
+            {
+                let e = em.add();
+
+                sprite.last = Some(entity);
+
+                cm.add(e, instance, em);
+                cm.add(e, sprite, em);
+                cm.add(e, Transform::new(position, 0.0, Vec2d([1.0; 2]), true), em);
+
+                if let Some(item) = cm
+                    .get_mut::<Construct>(entity)
+                    .and_then(|c| c.belt.as_mut())
+                    .and_then(|b| b.items.get_mut(i))
+                {
+                    item.entity = Some(e);
+                }
+            }
+        }
+    }
+
+    pub fn storage(id: String, capacity: u32, (player, index): (Id, Id)) -> Self {
+        Self {
+            id,
+            update: Rc::new(move |entity, (em, cm)| Self::store(entity, (player, index), (em, cm))),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: None,
+            crafter: None,
+            inventory: None,
+            belt: None,
+            storage: Some(Storage::new(capacity)),
+            power: None,
+            filter: None,
+            accept: Some(Rc::new(move |entity, item, _, (em, cm)| {
+                let stored = cm
+                    .get_mut::<Construct>(entity)
+                    .and_then(|c| c.storage.as_mut())
+                    .map(|s| s.insert(&item.id))
+                    .unwrap_or_default();
+
+                if stored {
+                    Self::despawn(item, index, (em, cm));
+                }
+
+                stored
+            })),
+            interact: None,
+        }
+    }
+
+    fn store(
+        entity: Id,
+        (player, index): (Id, Id),
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        Self::collect(entity, index, (em, cm));
+        Self::unload(entity, (player, index), (em, cm));
+
+        Ok(())
+    }
+
+    fn collect(entity: Id, index: Id, (em, cm): (&mut EntityManager, &mut ComponentManager)) {
+        let Some(accept) = cm.get::<Construct>(entity).and_then(|c| c.accept.clone()) else {
+            return;
+        };
+        let tile = cm
+            .get::<Transform>(entity)
+            .map(|t| SpatialIndex::tile_pos(t.position()))
+            .and_then(|tile| Some(cm.get::<SpatialIndex>(index)?.tile(tile)))
+            .unwrap_or_default();
+
+        for e in tile {
+            if let Some(id) = cm
+                .get::<Item>(e)
+                .filter(|i| i.last != Some(entity) && cm.get::<Physical>(e).is_some())
+                .map(|i| i.id.clone())
+            {
+                (*accept)(
+                    entity,
+                    &BeltItem {
+                        entity: Some(e),
+                        ..BeltItem::new(&id)
+                    },
+                    0,
+                    (em, cm),
+                );
+            }
+        }
+    }
+
+    pub fn generator(id: String, index: Id) -> Self {
+        Self {
+            id,
+            update: Rc::new(move |entity, (em, cm)| {
+                Self::collect(entity, index, (em, cm));
+
+                Ok(())
+            }),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: None,
+            crafter: None,
+            inventory: None,
+            belt: None,
+            storage: None,
+            power: None,
+            filter: None,
+            accept: Some(Rc::new(move |entity, item, _, (em, cm)| {
+                let fueled = cm
+                    .get_mut::<Construct>(entity)
+                    .and_then(|c| match &c.power.as_ref()?.kind {
+                        PowerKind::Generator {
+                            fuel: Some(fuel), ..
+                        } if *fuel == item.id => Some(c.inventory.as_mut()?.insert(&item.id)),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+
+                if fueled {
+                    Self::despawn(item, index, (em, cm));
+                }
+
+                fueled
+            })),
+            interact: None,
+        }
+    }
+
+    pub fn pole(id: String) -> Self {
+        Self {
+            id,
+            update: Rc::new(|_, _| Ok(())),
+            tick_amount: 0,
+            update_tick: 1,
+            mode: None,
+            crafter: None,
+            inventory: None,
+            belt: None,
+            storage: None,
+            power: None,
+            filter: None,
+            accept: None,
+            interact: None,
+        }
+    }
+
+    pub fn powered(&mut self) -> bool {
+        self.power.as_mut().map(|p| p.step()).unwrap_or(true)
+    }
+
+    fn output(&self) -> Option<&str> {
+        self.inventory
+            .as_ref()
+            .and_then(|i| i.peek())
+            .or_else(|| self.storage.as_ref()?.peek())
+    }
+
+    fn unload(
+        entity: Id,
+        (player, index): (Id, Id),
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) {
+        let Some((transform, item)) = cm.get::<Transform>(entity).cloned().zip(
+            cm.get::<Construct>(entity)
+                .and_then(|c| c.output())
+                .map(BeltItem::new),
+        ) else {
+            return;
+        };
+        let stored = cm
+            .get::<Construct>(entity)
+            .map(|c| c.inventory.is_none() && c.storage.is_some())
+            .unwrap_or_default();
+        let unloaded = if stored {
+            Self::ahead((transform.position(), transform.rotation()), index, cm)
+                .map(|target| Self::deliver(target, &item, 0, (em, cm)))
+                .unwrap_or_default()
+        } else {
+            Self::forward(
+                entity,
+                (transform.position(), transform.rotation()),
+                &item,
+                0,
+                (player, index),
+                (em, cm),
             )
-        }))
+        };
+
+        if unloaded {
+            if let Some(c) = cm.get_mut::<Construct>(entity) {
+                match (c.inventory.as_mut(), c.storage.as_mut()) {
+                    (Some(inventory), _) => {
+                        inventory.pop();
+                    }
+                    (None, Some(storage)) => {
+                        storage.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn forward(
+        entity: Id,
+        (position, rotation): (Vec2d, f32),
+        item: &BeltItem,
+        depth: u32,
+        (player, index): (Id, Id),
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> bool {
+        match Self::ahead((position, rotation), index, cm) {
+            Some(target) => Self::deliver(target, item, depth, (em, cm)),
+            None => Self::spill(
+                entity,
+                (position, Self::direction(rotation)),
+                item,
+                (player, index),
+                (em, cm),
+            ),
+        }
+    }
+
+    fn ahead((position, rotation): (Vec2d, f32), index: Id, cm: &ComponentManager) -> Option<Id> {
+        cm.get::<SpatialIndex>(index)
+            .map(|i| i.tile(SpatialIndex::tile_pos(position + Self::direction(rotation))))
+            .unwrap_or_default()
+            .into_iter()
+            .find(|e| cm.get::<Construct>(*e).is_some())
+    }
+
+    fn deliver(
+        target: Id,
+        item: &BeltItem,
+        depth: u32,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> bool {
+        if depth >= MAX_DELIVER_DEPTH {
+            return false;
+        }
+
+        cm.get::<Construct>(target)
+            .and_then(|c| c.accept.clone())
+            .map(|accept| (*accept)(target, item, depth + 1, (em, cm)))
+            .unwrap_or_default()
+    }
+
+    fn spill(
+        entity: Id,
+        (position, direction): (Vec2d, Vec2d),
+        item: &BeltItem,
+        (player, index): (Id, Id),
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> bool {
+        let position = position + direction * 0.5;
+        let blocked = cm
+            .get::<SpatialIndex>(index)
+            .map(|i| i.radius(position, EJECT_SPACING))
+            .unwrap_or_default()
+            .into_iter()
+            .any(|e| {
+                cm.get::<Item>(e)
+                    .map(|i| i.last == Some(entity) && cm.get::<Physical>(e).is_some())
+                    .unwrap_or_default()
+            });
+        let Some(mut spilled) = cm
+            .get::<State>(player)
+            .and_then(|s| s.items.get(&item.id).cloned())
+            .filter(|_| !blocked)
+        else {
+            return false;
+        };
+
+        Self::despawn(item, index, (em, cm));
+
+        let e = em.add();
+
+        spilled.0.last = Some(entity);
+
+        cm.add(e, spilled.1, em);
+        cm.add(e, spilled.0, em);
+        cm.add(e, Transform::new(position, 0.0, Vec2d([1.0; 2]), true), em);
+        cm.add(e, Physical::new(direction, true), em);
+
+        if let Some(index) = cm.get_mut::<SpatialIndex>(index) {
+            index.insert(e, position);
+        }
+
+        true
+    }
+
+    fn despawn(item: &BeltItem, index: Id, (em, cm): (&mut EntityManager, &mut ComponentManager)) {
+        if let Some(e) = item.entity {
+            if let Some(index) = cm.get_mut::<SpatialIndex>(index) {
+                index.remove(e);
+            }
+
+            em.rm(e, cm);
+        }
+    }
+
+    fn direction(rotation: f32) -> Vec2d {
+        (Mat3d::rotation(rotation) * (Vec2d::new(0.0, 1.0), 1.0)).0
+    }
+
+    fn nearby(index: Id, construct_transform: &Transform, cm: &ComponentManager) -> Vec<Id> {
+        cm.get::<SpatialIndex>(index)
+            .map(|i| i.radius(construct_transform.position(), PICKUP_RADIUS))
+            .unwrap_or_default()
     }
 
     fn pickup(construct_transform: &Transform, item_position: Vec2d, force: Vec2d) -> bool {
@@ -368,3 +1093,42 @@ impl Construct {
 }
 
 impl Component for Construct {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_filter_reaches_every_item() {
+        let items: Vec<_> = ["metal", "plate", "refined_metal"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        for manifest in [
+            vec!["refined_metal".to_string()],
+            vec!["metal".to_string()],
+            vec!["metal".to_string(), "refined_metal".to_string()],
+            Vec::new(),
+        ] {
+            let mut filter = manifest.clone();
+            let mut seen = Vec::new();
+
+            while seen.len() <= items.len() + 2 {
+                filter = Construct::next_filter(&filter, &manifest, &items);
+                seen.push(filter.clone());
+
+                if filter == manifest {
+                    break;
+                }
+            }
+
+            assert_eq!(filter, manifest);
+            assert!(seen.contains(&Vec::new()));
+
+            for item in &items {
+                assert!(seen.contains(&vec![item.clone()]));
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use super::{Grid, Inventory, RecipeStack};
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PowerKind {
+    Consumer {
+        draw: f32,
+    },
+    Generator {
+        supply: f32,
+        fuel: Option<String>,
+        burn_ticks: u32,
+    },
+    Pole {
+        range: f32,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Power {
+    pub kind: PowerKind,
+    pub burning: f32,
+    /// Set on consumers loaded from saves that predate power, which keep running
+    /// unpowered until they are first connected to a grid.
+    #[serde(default)]
+    pub legacy: bool,
+    #[serde(skip)]
+    pub satisfaction: f32,
+    #[serde(skip)]
+    pub charge: f32,
+    #[serde(skip)]
+    pub grid: Grid,
+}
+
+impl Power {
+    pub fn new(kind: PowerKind) -> Self {
+        Self {
+            kind,
+            burning: 0.0,
+            legacy: false,
+            satisfaction: 0.0,
+            charge: 0.0,
+            grid: Grid::default(),
+        }
+    }
+
+    pub fn restore(&mut self, saved: &Power) {
+        self.burning = saved.burning;
+        self.legacy = saved.legacy;
+    }
+
+    pub fn draw(&self) -> f32 {
+        match self.kind {
+            PowerKind::Consumer { draw } => draw,
+            _ => 0.0,
+        }
+    }
+
+    pub fn available(&self, inventory: Option<&Inventory>) -> f32 {
+        match &self.kind {
+            PowerKind::Generator {
+                supply, fuel: None, ..
+            } => *supply,
+            PowerKind::Generator {
+                supply,
+                fuel: Some(fuel),
+                ..
+            } if self.burning > 0.0 || inventory.map(|i| i.count(fuel) > 0).unwrap_or_default() => {
+                *supply
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn burn(&mut self, load: f32, inventory: Option<&mut Inventory>) {
+        if let PowerKind::Generator {
+            fuel: Some(fuel),
+            burn_ticks,
+            ..
+        } = &self.kind
+        {
+            if self.burning <= 0.0 && load > 0.0 {
+                if let Some(inventory) = inventory {
+                    let stack = [RecipeStack::new(fuel, 1)];
+
+                    if inventory.has(&stack) {
+                        inventory.take(&stack);
+
+                        self.burning += *burn_ticks as f32;
+                    }
+                }
+            }
+
+            self.burning = (self.burning - load).max(0.0);
+        }
+    }
+
+    pub fn supply(&mut self, grid: Grid, inventory: Option<&mut Inventory>) {
+        self.legacy &= !grid.connected;
+        self.grid = grid;
+        self.satisfaction = if self.legacy {
+            1.0
+        } else {
+            grid.satisfaction()
+        };
+        self.burn(grid.load(), inventory);
+    }
+
+    pub fn step(&mut self) -> bool {
+        if self.draw() <= 0.0 {
+            return true;
+        }
+
+        self.charge += self.satisfaction;
+
+        if self.charge >= 1.0 {
+            self.charge -= 1.0;
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let Grid { supply, demand, .. } = self.grid;
+        let grid = format!("Grid: {supply:.0}/{demand:.0}");
+
+        match &self.kind {
+            PowerKind::Consumer { draw } if !self.grid.connected => format!(
+                "Power: {draw} unconnected{}",
+                if self.legacy { " (legacy)" } else { "" }
+            ),
+            PowerKind::Consumer { draw } => format!(
+                "Power: {draw} at {}% | {grid}",
+                (self.satisfaction * 100.0).round()
+            ),
+            PowerKind::Generator {
+                burn_ticks,
+                fuel: Some(_),
+                ..
+            } => format!(
+                "Fuel: {}% | {grid}",
+                (self.burning * 100.0 / *burn_ticks as f32).round()
+            ),
+            _ => grid,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construct::construct_manifest::InventoryManifest;
+    use hex::anyhow;
+
+    fn generator() -> (Power, Inventory) {
+        let power = Power::new(PowerKind::Generator {
+            supply: 10.0,
+            fuel: Some("ice".to_string()),
+            burn_ticks: 4,
+        });
+        let inventory = Inventory::new(&InventoryManifest {
+            inputs: 1,
+            outputs: 0,
+            capacity: 5,
+        });
+
+        (power, inventory)
+    }
+
+    #[test]
+    fn step_accumulates_partial_satisfaction() {
+        let mut power = Power::new(PowerKind::Consumer { draw: 5.0 });
+
+        power.satisfaction = 0.5;
+
+        let steps: Vec<_> = (0..4).map(|_| power.step()).collect();
+
+        assert_eq!(steps, [false, true, false, true]);
+
+        power.satisfaction = 0.0;
+
+        assert!(!power.step());
+        assert!(Power::new(PowerKind::Pole { range: 3.0 }).step());
+    }
+
+    #[test]
+    fn available_needs_fuel_or_a_burning_charge() {
+        let (mut power, mut inventory) = generator();
+
+        assert_eq!(power.available(Some(&inventory)), 0.0);
+
+        inventory.insert("ice");
+
+        assert_eq!(power.available(Some(&inventory)), 10.0);
+
+        inventory.take(&[RecipeStack::new("ice", 1)]);
+        power.burning = 1.0;
+
+        assert_eq!(power.available(None), 10.0);
+    }
+
+    #[test]
+    fn burn_takes_fuel_only_when_spent_and_loaded() {
+        let (mut power, mut inventory) = generator();
+
+        inventory.insert("ice");
+        inventory.insert("ice");
+        power.burn(0.0, Some(&mut inventory));
+
+        assert_eq!(inventory.count("ice"), 2);
+
+        power.burn(1.0, Some(&mut inventory));
+
+        assert_eq!(inventory.count("ice"), 1);
+        assert_eq!(power.burning, 3.0);
+
+        for _ in 0..3 {
+            power.burn(1.0, Some(&mut inventory));
+        }
+
+        assert_eq!(inventory.count("ice"), 1);
+        assert_eq!(power.burning, 0.0);
+
+        power.burn(0.5, Some(&mut inventory));
+
+        assert_eq!(inventory.count("ice"), 0);
+        assert_eq!(power.burning, 3.5);
+    }
+
+    #[test]
+    fn restore_keeps_burning_and_legacy() -> anyhow::Result<()> {
+        let (mut saved, _) = generator();
+
+        saved.burning = 2.5;
+        saved.legacy = true;
+        saved.satisfaction = 1.0;
+
+        let saved: Power = serde_json::from_str(&serde_json::to_string(&saved)?)?;
+        let (mut power, _) = generator();
+
+        power.restore(&saved);
+
+        assert_eq!(power.burning, 2.5);
+        assert!(power.legacy);
+        assert_eq!(power.satisfaction, 0.0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use hex::anyhow;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecipeStack {
+    pub item: String,
+    pub count: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    pub id: String,
+    pub inputs: Vec<RecipeStack>,
+    pub outputs: Vec<RecipeStack>,
+    pub ticks: u32,
+    pub machines: Vec<String>,
+}
+
+impl RecipeStack {
+    pub fn new(item: &str, count: u32) -> Self {
+        Self {
+            item: item.to_string(),
+            count,
+        }
+    }
+}
+
+impl Recipe {
+    pub fn for_machine(recipes: &HashMap<String, Recipe>, machine: &str) -> Vec<Recipe> {
+        let mut recipes: Vec<_> = recipes
+            .values()
+            .filter(|r| r.machines.iter().any(|m| m == machine))
+            .cloned()
+            .collect();
+
+        recipes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        recipes
+    }
+
+    pub fn validate(&self, machines: &[&str]) -> anyhow::Result<()> {
+        if self.inputs.is_empty() || self.outputs.is_empty() {
+            anyhow::bail!("Recipe \"{}\" needs at least one input and output", self.id);
+        }
+
+        if let Some(stack) = self
+            .inputs
+            .iter()
+            .chain(&self.outputs)
+            .find(|s| s.count == 0)
+        {
+            anyhow::bail!("Recipe \"{}\" uses 0 of \"{}\"", self.id, stack.item);
+        }
+
+        if self.ticks == 0 {
+            anyhow::bail!("Recipe \"{}\" must take at least one tick", self.id);
+        }
+
+        if let Some(machine) = self
+            .machines
+            .iter()
+            .find(|m| !machines.contains(&m.as_str()))
+        {
+            anyhow::bail!(
+                "Recipe \"{}\" runs on \"{machine}\", which is not a machine",
+                self.id
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn input(&self, item: &str) -> Option<u32> {
+        self.inputs.iter().find(|i| i.item == item).map(|i| i.count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe() -> Recipe {
+        Recipe {
+            id: "refined_metal".to_string(),
+            inputs: vec![RecipeStack::new("metal", 1)],
+            outputs: vec![RecipeStack::new("refined_metal", 1)],
+            ticks: 50,
+            machines: vec!["furnace".to_string()],
+        }
+    }
+
+    #[test]
+    fn validate_rejects_malformed_recipes() {
+        let machines = ["furnace", "assembler"];
+
+        assert!(recipe().validate(&machines).is_ok());
+        assert!(Recipe {
+            inputs: Vec::new(),
+            ..recipe()
+        }
+        .validate(&machines)
+        .is_err());
+        assert!(Recipe {
+            outputs: vec![RecipeStack::new("refined_metal", 0)],
+            ..recipe()
+        }
+        .validate(&machines)
+        .is_err());
+        assert!(Recipe {
+            ticks: 0,
+            ..recipe()
+        }
+        .validate(&machines)
+        .is_err());
+        assert!(recipe().validate(&["assembler"]).is_err());
+    }
+
+    #[test]
+    fn for_machine_sorts_matching_recipes() {
+        let recipes = HashMap::from([
+            ("refined_metal".to_string(), recipe()),
+            (
+                "alloy".to_string(),
+                Recipe {
+                    id: "alloy".to_string(),
+                    ..recipe()
+                },
+            ),
+            (
+                "plate".to_string(),
+                Recipe {
+                    id: "plate".to_string(),
+                    machines: vec!["assembler".to_string()],
+                    ..recipe()
+                },
+            ),
+        ]);
+        let ids: Vec<_> = Recipe::for_machine(&recipes, "furnace")
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+
+        assert_eq!(ids, ["alloy", "refined_metal"]);
+        assert_eq!(recipe().input("metal"), Some(1));
+        assert_eq!(recipe().input("ice"), None);
+    }
+}
@@ -0,0 +1,123 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Storage {
+    pub items: VecDeque<String>,
+    pub capacity: u32,
+}
+
+impl Storage {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            items: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn restore(&mut self, saved: &Storage) {
+        self.items = saved.items.clone();
+    }
+
+    pub fn insert(&mut self, item: &str) -> bool {
+        if self.items.len() < self.capacity as usize {
+            self.items.push_back(item.to_string());
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn peek(&self) -> Option<&str> {
+        self.items.front().map(|i| i.as_str())
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        self.items.pop_front()
+    }
+
+    pub fn describe(&self) -> String {
+        let mut counts: Vec<(&str, u32)> = Vec::new();
+
+        for item in &self.items {
+            match counts.iter_mut().find(|(i, _)| i == item) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((item, 1)),
+            }
+        }
+
+        let counts: Vec<_> = counts
+            .into_iter()
+            .map(|(item, count)| format!("{item} {count}"))
+            .collect();
+
+        format!(
+            "Stored {}/{}: {}",
+            self.items.len(),
+            self.capacity,
+            if counts.is_empty() {
+                "empty".to_string()
+            } else {
+                counts.join(", ")
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex::anyhow;
+
+    #[test]
+    fn insert_stops_at_capacity() {
+        let mut storage = Storage::new(2);
+
+        assert!(storage.insert("metal"));
+        assert!(storage.insert("ice"));
+        assert!(!storage.insert("metal"));
+        assert_eq!(storage.items.len(), 2);
+
+        storage.pop();
+
+        assert!(storage.insert("metal"));
+    }
+
+    #[test]
+    fn pop_unloads_in_arrival_order() {
+        let mut storage = Storage::new(3);
+
+        for item in ["metal", "ice", "metal"] {
+            storage.insert(item);
+        }
+
+        assert_eq!(storage.peek(), Some("metal"));
+        assert_eq!(storage.pop().as_deref(), Some("metal"));
+        assert_eq!(storage.peek(), Some("ice"));
+        assert_eq!(storage.pop().as_deref(), Some("ice"));
+        assert_eq!(storage.pop().as_deref(), Some("metal"));
+        assert_eq!(storage.pop(), None);
+    }
+
+    #[test]
+    fn restore_keeps_order_and_manifest_capacity() -> anyhow::Result<()> {
+        let mut saved = Storage::new(4);
+
+        for item in ["ice", "metal", "ice"] {
+            saved.insert(item);
+        }
+
+        let saved: Storage = serde_json::from_str(&serde_json::to_string(&saved)?)?;
+        let mut storage = Storage::new(3);
+
+        storage.restore(&saved);
+
+        assert_eq!(storage.capacity, 3);
+        assert_eq!(storage.items, ["ice", "metal", "ice"]);
+        assert_eq!(storage.describe(), "Stored 3/3: ice 2, metal 1");
+        assert_eq!(Storage::new(3).describe(), "Stored 0/3: empty");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    construct::Construct,
+    player::{state::GAME_MODE, Player, State},
+    util,
+};
+use hex::{
+    anyhow,
+    assets::Shape,
+    components::Sprite,
+    ecs::{ComponentManager, EntityManager, Id},
+    glium::Display,
+    math::Vec2d,
+};
+use hex_ui::{ab_glyph::FontRef, ScreenTransform};
+use std::time::{Duration, Instant};
+
+pub const INSPECT_COLOR: [f32; 4] = [0.8, 1.0, 0.8, 1.0];
+pub const INSPECT_HEIGHT: f32 = 0.4;
+pub const INSPECT_POSITION: Vec2d = Vec2d([0.0, 5.5]);
+pub const INSPECT_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct Inspector {
+    pub font: FontRef<'static>,
+    pub label: Id,
+    pub text: String,
+    pub hovered: Option<Id>,
+    pub updated: Instant,
+}
+
+impl Inspector {
+    pub fn new(
+        display: &Display,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<Self> {
+        let font = FontRef::try_from_slice(include_bytes!("font.otf"))?;
+        let label = em.add();
+
+        cm.add(
+            label,
+            Sprite::new(
+                Shape::rect(display, Vec2d([1.0; 2]))?,
+                util::text_texture(display, &font, " ", INSPECT_COLOR)?,
+                [1.0; 4],
+                4.0,
+                false,
+            ),
+            em,
+        );
+        cm.add(
+            label,
+            ScreenTransform::new(INSPECT_POSITION, 0.0, Vec2d([1.0; 2]), true),
+            em,
+        );
+
+        Ok(Self {
+            font,
+            label,
+            text: String::new(),
+            hovered: None,
+            updated: Instant::now(),
+        })
+    }
+
+    pub fn update(
+        &mut self,
+        display: &Display,
+        player: Id,
+        cm: &mut ComponentManager,
+    ) -> anyhow::Result<()> {
+        let hovered = cm
+            .get::<State>(player)
+            .filter(|s| s.mode == GAME_MODE)
+            .and_then(|_| cm.get::<Player>(player)?.hovered);
+        let now = Instant::now();
+
+        if hovered == self.hovered && now.duration_since(self.updated) < INSPECT_INTERVAL {
+            return Ok(());
+        }
+
+        self.hovered = hovered;
+        self.updated = now;
+
+        let text = hovered
+            .and_then(|e| {
+                Some(
+                    cm.get::<Construct>(e)?
+                        .describe(&cm.get::<State>(player)?.recipes),
+                )
+            })
+            .unwrap_or_default();
+
+        if text == self.text {
+            return Ok(());
+        }
+
+        if let Some(sprite) = cm.get_mut::<Sprite>(self.label) {
+            sprite.active = !text.is_empty();
+        }
+
+        if !text.is_empty() {
+            let texture = util::text_texture(display, &self.font, &text, INSPECT_COLOR)?;
+            let (width, height) = texture.buffer.dimensions();
+
+            if let Some(sprite) = cm.get_mut::<Sprite>(self.label) {
+                sprite.texture = texture;
+            }
+
+            if let Some(transform) = cm.get_mut::<ScreenTransform>(self.label) {
+                transform.scale = Vec2d::new(
+                    INSPECT_HEIGHT * width as f32 / height.max(1) as f32,
+                    INSPECT_HEIGHT,
+                );
+            }
+        }
+
+        self.text = text;
+
+        Ok(())
+    }
+}
@@ -1,28 +1,45 @@
-use crate::player::{state::MENU_MODE, State};
-use crate::util;
+use super::{menu_button::TEXT_COLOR, MenuButton, NewWorldMenu};
+use crate::{
+    chunk::Map,
+    player::{
+        state::{GAME_MODE, MENU_MODE},
+        SaveData, State, World,
+    },
+    util, Tag,
+};
 use hex::{
     anyhow,
-    assets::{Shape, Texture},
+    assets::Shape,
     components::Sprite,
     ecs::{ComponentManager, EntityManager, Id},
-    glium::{
-        texture::MipmapsOption,
-        uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior},
-        Display,
-    },
+    glium::Display,
     math::Vec2d,
 };
-use hex_ui::{
-    ab_glyph::FontRef,
-    ui::{Button, Callback},
-    ScreenTransform, Text, Ui,
-};
+use hex_ui::{ab_glyph::FontRef, ScreenTransform};
+
+pub const WORLD_PAGE_SIZE: usize = 6;
+pub const TEXT_SCALE: f32 = 8.0;
 
 pub struct MainMenu {
     pub font: FontRef<'static>,
-    pub text: Id,
-    pub button: Id,
     pub window: Id,
+    pub status: Id,
+    pub input: Id,
+    pub entries: Vec<MenuButton>,
+    pub create: MenuButton,
+    pub load: MenuButton,
+    pub rename: MenuButton,
+    pub delete: MenuButton,
+    pub prev: MenuButton,
+    pub next: MenuButton,
+    pub new_world: NewWorldMenu,
+    pub worlds: Vec<World>,
+    pub selected: Option<World>,
+    pub name: String,
+    pub message: String,
+    pub page: usize,
+    pub confirm_delete: bool,
+    pub refresh: bool,
 }
 
 impl MainMenu {
@@ -31,62 +48,6 @@ impl MainMenu {
         (em, cm): (&mut EntityManager, &mut ComponentManager),
     ) -> anyhow::Result<Self> {
         let font = FontRef::try_from_slice(include_bytes!("font.otf"))?;
-        let text_texture = Texture::text(
-            display,
-            "Play",
-            font.clone(),
-            (50.0, 0, 1000.0),
-            [0.0, 0.25, 0.0, 1.0],
-            MipmapsOption::AutoGeneratedMipmaps,
-            SamplerBehavior {
-                magnify_filter: MagnifySamplerFilter::Nearest,
-                minify_filter: MinifySamplerFilter::Nearest,
-                ..Default::default()
-            },
-        )?;
-        let text_sprite = Sprite::new(
-            Shape::rect(display, Vec2d([1.0; 2]))?,
-            text_texture,
-            [1.0; 4],
-            2.0,
-            true,
-        );
-        let text = em.add();
-
-        cm.add(text, text_sprite, em);
-
-        cm.add(
-            text,
-            ScreenTransform::new(Vec2d([0.0; 2]), 0.0, Vec2d([1.0; 2]), true),
-            em,
-        );
-
-        let button_texture = util::load_texture(display, include_bytes!("button.png"))?;
-        let button_sprite = Sprite::new(
-            Shape::rect(display, Vec2d([1.0; 2]))?,
-            button_texture,
-            [1.0; 4],
-            1.0,
-            true,
-        );
-        let button = em.add();
-
-        cm.add(button, button_sprite, em);
-        cm.add(
-            button,
-            ScreenTransform::new(Vec2d([0.0; 2]), 0.0, Vec2d([5.0; 2]), true),
-            em,
-        );
-        cm.add(
-            button,
-            Box::new(Button {
-                dimensions: Vec2d::new(1.0, 0.5),
-                active: true,
-            }) as Box<dyn Ui>,
-            em,
-        );
-        cm.add(button, Callback::default(), em);
-
         let window_texture = util::load_texture(display, include_bytes!("window.png"))?;
         let window_sprite = Sprite::new(
             Shape::rect(display, Vec2d([1.0; 2]))?,
@@ -104,27 +65,372 @@ impl MainMenu {
             em,
         );
 
+        let status = Self::label(display, &font, Vec2d::new(0.0, 4.0), (em, cm))?;
+        let input = Self::label(display, &font, Vec2d::new(0.0, 3.2), (em, cm))?;
+        let entries = (0..WORLD_PAGE_SIZE)
+            .map(|i| {
+                MenuButton::new(
+                    display,
+                    &font,
+                    ("", Vec2d::new(-1.5, 2.2 - i as f32 * 0.9), 4.0),
+                    (em, cm),
+                )
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let mut button =
+            |label, position| MenuButton::new(display, &font, (label, position, 3.0), (em, cm));
+        let create = button("New", Vec2d::new(2.5, 2.2))?;
+        let load = button("Play", Vec2d::new(2.5, 1.3))?;
+        let rename = button("Rename", Vec2d::new(2.5, 0.4))?;
+        let delete = button("Delete", Vec2d::new(2.5, -0.5))?;
+        let prev = button("<", Vec2d::new(-2.5, -3.4))?;
+        let next = button(">", Vec2d::new(-0.5, -3.4))?;
+        let new_world = NewWorldMenu::new(display, &font, (em, cm))?;
+
         Ok(Self {
             font,
-            text,
-            button,
             window,
+            status,
+            input,
+            entries,
+            create,
+            load,
+            rename,
+            delete,
+            prev,
+            next,
+            new_world,
+            worlds: World::list()?,
+            selected: None,
+            name: String::new(),
+            message: "Press New to create a world, or select one".to_string(),
+            page: 0,
+            confirm_delete: false,
+            refresh: true,
         })
     }
 
-    pub fn update(&self, player: Id, (_, cm): (&mut EntityManager, &mut ComponentManager)) {
+    fn label(
+        display: &Display,
+        font: &FontRef<'static>,
+        position: Vec2d,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<Id> {
+        let label = em.add();
+
+        cm.add(
+            label,
+            Sprite::new(
+                Shape::rect(display, Vec2d([1.0; 2]))?,
+                MenuButton::label_texture(display, font, "")?,
+                [1.0; 4],
+                2.0,
+                true,
+            ),
+            em,
+        );
+        cm.add(
+            label,
+            ScreenTransform::new(position, 0.0, Vec2d([1.0; 2]), true),
+            em,
+        );
+
+        Ok(label)
+    }
+
+    fn set_text(
+        &self,
+        display: &Display,
+        label: Id,
+        text: &str,
+        cm: &mut ComponentManager,
+    ) -> anyhow::Result<()> {
+        let texture = util::text_texture(
+            display,
+            &self.font,
+            if text.is_empty() { " " } else { text },
+            TEXT_COLOR,
+        )?;
+        let scale = util::fit_text(texture.buffer.dimensions(), TEXT_SCALE);
+
+        if let Some(sprite) = cm.get_mut::<Sprite>(label) {
+            sprite.texture = texture;
+        }
+
+        if let Some(transform) = cm.get_mut::<ScreenTransform>(label) {
+            transform.scale = scale;
+        }
+
+        Ok(())
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        if self.new_world.open {
+            self.new_world.type_char(c);
+        } else if c == '\u{8}' {
+            self.name.pop();
+        } else if !c.is_control() {
+            self.name.push(c);
+        }
+
+        self.refresh = true;
+    }
+
+    pub fn update(
+        &mut self,
+        display: &Display,
+        player: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        let pressed: Vec<_> = self
+            .entries
+            .iter()
+            .chain([
+                &self.create,
+                &self.load,
+                &self.rename,
+                &self.delete,
+                &self.prev,
+                &self.next,
+            ])
+            .map(|b| b.pressed(cm))
+            .collect();
+        let new_world = self.new_world.pressed(cm);
+
         if let Some(active) = cm.get::<State>(player).map(|p| p.mode == MENU_MODE) {
-            if let Some(text) = cm.get_mut::<Sprite>(self.text) {
-                text.active = active;
+            let browsing = active && !self.new_world.open;
+
+            for (e, active) in [
+                (self.window, active),
+                (self.status, active),
+                (self.input, browsing),
+            ] {
+                if let Some(sprite) = cm.get_mut::<Sprite>(e) {
+                    sprite.active = active;
+                }
             }
 
-            if let Some(button) = cm.get_mut::<Sprite>(self.button) {
-                button.active = active;
+            self.new_world.set_active(active && self.new_world.open, cm);
+
+            for b in [
+                &self.create,
+                &self.load,
+                &self.rename,
+                &self.delete,
+                &self.prev,
+                &self.next,
+            ] {
+                b.set_active(browsing, cm);
             }
 
-            if let Some(window) = cm.get_mut::<Sprite>(self.window) {
-                window.active = active;
+            let offset = self.page * WORLD_PAGE_SIZE;
+
+            for (i, entry) in self.entries.iter().enumerate() {
+                entry.set_active(browsing && self.worlds.get(offset + i).is_some(), cm);
             }
+
+            if !active {
+                return Ok(());
+            }
+
+            if let Some(error) = Tag::new("map")
+                .find((em, cm))
+                .and_then(|m| cm.get_mut::<Map>(m)?.error.take())
+            {
+                self.message = error;
+                self.refresh = true;
+            }
+
+            if self.new_world.open {
+                match self.new_world.handle(new_world) {
+                    Ok(Some(world)) => {
+                        self.message = format!("Created \"{}\"", world.name);
+                        self.select(world)?;
+                    }
+                    Ok(None) => self.refresh |= !self.new_world.open,
+                    Err(e) => {
+                        self.message = format!("{e:#}");
+                        self.refresh = true;
+                    }
+                }
+
+                if self.new_world.refresh {
+                    self.new_world.refresh = false;
+                    self.new_world.redraw(display, &self.font, cm)?;
+                }
+
+                if self.refresh {
+                    self.refresh = false;
+                    self.redraw(display, cm)?;
+                }
+
+                return Ok(());
+            }
+
+            let (entries, buttons) = pressed.split_at(WORLD_PAGE_SIZE);
+
+            if let Some(world) = entries
+                .iter()
+                .position(|p| *p)
+                .and_then(|i| self.worlds.get(offset + i))
+            {
+                self.name = world.name.clone();
+                self.selected = Some(world.clone());
+                self.confirm_delete = false;
+                self.refresh = true;
+            }
+
+            if let Err(e) = self.handle(buttons, player, (em, cm)) {
+                self.message = format!("{e:#}");
+                self.confirm_delete = false;
+                self.refresh = true;
+            }
+
+            if self.refresh {
+                self.refresh = false;
+                self.redraw(display, cm)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle(
+        &mut self,
+        buttons: &[bool],
+        player: Id,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        let [create, load, rename, delete, prev, next] = buttons else {
+            return Ok(());
+        };
+        let open = cm.get::<State>(player).and_then(|s| s.world.clone());
+
+        if *create {
+            self.new_world.show(&self.name);
+            self.message = "Choose the settings for the new world".to_string();
+            self.confirm_delete = false;
+            self.refresh = true;
+
+            return Ok(());
+        }
+
+        if *load {
+            let world = self.selected()?;
+
+            if open.as_ref() == Some(&world) {
+                if let Some(state) = cm.get_mut::<State>(player) {
+                    state.mode = GAME_MODE;
+                }
+            } else {
+                let save_data = SaveData::load(&world)?;
+
+                if let Some(map) = Tag::new("map")
+                    .find((em, cm))
+                    .and_then(|m| cm.get_mut::<Map>(m))
+                {
+                    map.open = Some((world.clone(), save_data));
+                }
+
+                self.message = format!("Playing \"{}\"", world.name);
+            }
+
+            self.refresh = true;
+        }
+
+        if *rename {
+            let world = self.selected()?;
+
+            if open.as_ref() == Some(&world) {
+                anyhow::bail!("\"{}\" is being played and cannot be renamed", world.name);
+            }
+
+            let renamed = world.rename(&self.name)?;
+
+            self.message = format!("Renamed \"{}\" to \"{}\"", world.name, renamed.name);
+            self.select(renamed)?;
+        }
+
+        if *delete {
+            let world = self.selected()?;
+
+            if open.as_ref() == Some(&world) {
+                anyhow::bail!("\"{}\" is being played and cannot be deleted", world.name);
+            }
+
+            if self.confirm_delete {
+                world.delete()?;
+
+                self.message = format!("Deleted \"{}\"", world.name);
+                self.selected = None;
+                self.confirm_delete = false;
+                self.worlds = World::list()?;
+                self.page = self.page.min(self.pages() - 1);
+            } else {
+                self.message = format!("Press Delete again to delete \"{}\"", world.name);
+                self.confirm_delete = true;
+            }
+
+            self.refresh = true;
+        }
+
+        if *prev && self.page > 0 {
+            self.page -= 1;
+            self.refresh = true;
+        }
+
+        if *next && self.page + 1 < self.pages() {
+            self.page += 1;
+            self.refresh = true;
         }
+
+        Ok(())
+    }
+
+    fn selected(&self) -> anyhow::Result<World> {
+        self.selected
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Select a world first"))
+    }
+
+    fn select(&mut self, world: World) -> anyhow::Result<()> {
+        self.worlds = World::list()?;
+        self.page = self
+            .worlds
+            .iter()
+            .position(|w| *w == world)
+            .map(|i| i / WORLD_PAGE_SIZE)
+            .unwrap_or_default();
+        self.name = world.name.clone();
+        self.selected = Some(world);
+        self.confirm_delete = false;
+        self.refresh = true;
+
+        Ok(())
+    }
+
+    fn pages(&self) -> usize {
+        self.worlds.len().div_ceil(WORLD_PAGE_SIZE).max(1)
+    }
+
+    fn redraw(&self, display: &Display, cm: &mut ComponentManager) -> anyhow::Result<()> {
+        self.set_text(display, self.status, &self.message, cm)?;
+        self.set_text(display, self.input, &format!("Name: {}_", self.name), cm)?;
+
+        let offset = self.page * WORLD_PAGE_SIZE;
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            let world = self.worlds.get(offset + i);
+
+            entry.set_label(
+                display,
+                &self.font,
+                world.map(|w| w.name.as_str()).unwrap_or_default(),
+                cm,
+            )?;
+            entry.set_selected(world.is_some() && world == self.selected.as_ref(), cm);
+        }
+
+        Ok(())
     }
 }
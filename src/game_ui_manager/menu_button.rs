@@ -0,0 +1,151 @@
+use crate::util;
+use hex::{
+    anyhow,
+    assets::{Shape, Texture},
+    components::Sprite,
+    ecs::{ComponentManager, EntityManager, Id},
+    glium::Display,
+    math::Vec2d,
+};
+use hex_ui::{
+    ab_glyph::FontRef,
+    ui::{Button, Callback},
+    ScreenTransform, Ui,
+};
+
+pub const TEXT_COLOR: [f32; 4] = [0.0, 0.25, 0.0, 1.0];
+pub const SELECTED_COLOR: [f32; 4] = [0.6, 1.0, 0.6, 1.0];
+
+pub struct MenuButton {
+    pub button: Id,
+    pub text: Id,
+    pub scale: f32,
+}
+
+impl MenuButton {
+    pub fn new(
+        display: &Display,
+        font: &FontRef<'static>,
+        (label, position, scale): (&str, Vec2d, f32),
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<Self> {
+        let button = em.add();
+
+        cm.add(
+            button,
+            Sprite::new(
+                Shape::rect(display, Vec2d([1.0; 2]))?,
+                util::load_texture(display, include_bytes!("button.png"))?,
+                [1.0; 4],
+                1.0,
+                true,
+            ),
+            em,
+        );
+        cm.add(
+            button,
+            ScreenTransform::new(position, 0.0, Vec2d([scale; 2]), true),
+            em,
+        );
+        cm.add(
+            button,
+            Box::new(Button {
+                dimensions: Vec2d::new(scale / 5.0, scale / 10.0),
+                active: true,
+            }) as Box<dyn Ui>,
+            em,
+        );
+        cm.add(button, Callback::default(), em);
+
+        let text = em.add();
+
+        cm.add(
+            text,
+            Sprite::new(
+                Shape::rect(display, Vec2d([1.0; 2]))?,
+                Self::label_texture(display, font, label)?,
+                [1.0; 4],
+                2.0,
+                true,
+            ),
+            em,
+        );
+        cm.add(
+            text,
+            ScreenTransform::new(position, 0.0, Vec2d([1.0; 2]), true),
+            em,
+        );
+
+        let button = Self {
+            button,
+            text,
+            scale,
+        };
+
+        button.fit_label(cm);
+
+        Ok(button)
+    }
+
+    pub fn label_texture(
+        display: &Display,
+        font: &FontRef<'static>,
+        label: &str,
+    ) -> anyhow::Result<Texture> {
+        util::text_texture(
+            display,
+            font,
+            if label.is_empty() { " " } else { label },
+            TEXT_COLOR,
+        )
+    }
+
+    pub fn set_label(
+        &self,
+        display: &Display,
+        font: &FontRef<'static>,
+        label: &str,
+        cm: &mut ComponentManager,
+    ) -> anyhow::Result<()> {
+        let texture = Self::label_texture(display, font, label)?;
+
+        if let Some(sprite) = cm.get_mut::<Sprite>(self.text) {
+            sprite.texture = texture;
+        }
+
+        self.fit_label(cm);
+
+        Ok(())
+    }
+
+    pub fn fit_label(&self, cm: &mut ComponentManager) {
+        let (width, height) = cm
+            .get::<Sprite>(self.text)
+            .map(|s| s.texture.buffer.dimensions())
+            .unwrap_or((1, 1));
+
+        if let Some(transform) = cm.get_mut::<ScreenTransform>(self.text) {
+            transform.scale = util::fit_text((width, height), self.scale);
+        }
+    }
+
+    pub fn set_active(&self, active: bool, cm: &mut ComponentManager) {
+        for e in [self.button, self.text] {
+            if let Some(sprite) = cm.get_mut::<Sprite>(e) {
+                sprite.active = active;
+            }
+        }
+    }
+
+    pub fn set_selected(&self, selected: bool, cm: &mut ComponentManager) {
+        if let Some(sprite) = cm.get_mut::<Sprite>(self.button) {
+            sprite.color = if selected { SELECTED_COLOR } else { [1.0; 4] };
+        }
+    }
+
+    pub fn pressed(&self, cm: &mut ComponentManager) -> bool {
+        cm.get_mut::<Callback>(self.button)
+            .map(|c| c.check())
+            .unwrap_or_default()
+    }
+}
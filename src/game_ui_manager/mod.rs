@@ -1,10 +1,17 @@
 pub mod input;
+pub mod inspector;
 pub mod main_menu;
+pub mod menu_button;
+pub mod new_world_menu;
 
 pub use input::Input;
+pub use inspector::Inspector;
 pub use main_menu::MainMenu;
+pub use menu_button::MenuButton;
+pub use new_world_menu::NewWorldMenu;
 
 use crate::{
+    chunk::Map,
     player::{
         player_manager::CAM_DIMS,
         state::{GAME_MODE, MENU_MODE},
@@ -30,7 +37,6 @@ use hex::{
     },
     math::Vec2d,
 };
-use hex_ui::ui::Callback;
 use std::{cell::OnceCell, collections::HashMap, f32::consts::PI};
 
 pub const ZOOM: f32 = 5.0;
@@ -52,6 +58,7 @@ pub struct GameUiManager {
     camera: OnceCell<Option<Id>>,
     kp_cb: Binds,
     main_menu: MainMenu,
+    inspector: Inspector,
     window_x: f32,
     window_y: f32,
 }
@@ -68,6 +75,7 @@ impl GameUiManager {
             camera: Default::default(),
             kp_cb: Default::default(),
             main_menu: MainMenu::new(&context.display, (em, cm))?,
+            inspector: Inspector::new(&context.display, (em, cm))?,
             window_x: window_x as f32,
             window_y: window_y as f32,
         })
@@ -177,6 +185,20 @@ impl GameUiManager {
                     Ok(())
                 },
             );
+            self.add_keybind(
+                Input::Keyboard(VirtualKeyCode::E),
+                move |state, _, (_em, cm)| {
+                    if let ElementState::Pressed = state {
+                        let playing = cm.get::<State>(player).map(|s| s.mode) == Some(GAME_MODE);
+
+                        if let Some(player) = cm.get_mut::<Player>(player).filter(|_| playing) {
+                            player.states.interacting = true;
+                        }
+                    }
+
+                    Ok(())
+                },
+            );
             self.add_keybind(
                 Input::Keyboard(VirtualKeyCode::Tab),
                 move |state, _, (_em, cm)| {
@@ -201,6 +223,21 @@ impl GameUiManager {
                     Ok(())
                 },
             );
+            self.add_keybind(
+                Input::Keyboard(VirtualKeyCode::F2),
+                move |state, _, (em, cm)| {
+                    if let ElementState::Pressed = state {
+                        if let Some(map) = Tag::new("map")
+                            .find((em, cm))
+                            .and_then(|m| cm.get_mut::<Map>(m))
+                        {
+                            map.export = true;
+                        }
+                    }
+
+                    Ok(())
+                },
+            );
             self.add_keybind(
                 Input::Keyboard(VirtualKeyCode::Escape),
                 move |state, _, (_em, cm)| {
@@ -243,18 +280,8 @@ impl System for GameUiManager {
                     .player
                     .get_or_init(|| Tag::new("player").find((em, cm)))
                 {
-                    self.main_menu.update(player, (em, cm));
-
-                    if let Some(pressed) = cm
-                        .get_mut::<Callback>(self.main_menu.button)
-                        .map(|c| c.check())
-                    {
-                        if pressed {
-                            if let Some(state) = cm.get_mut::<State>(player) {
-                                state.mode = GAME_MODE;
-                            }
-                        }
-                    }
+                    self.main_menu.update(&context.display, player, (em, cm))?;
+                    self.inspector.update(&context.display, player, cm)?;
                 }
             }
             Ev::Event(Control {
@@ -289,6 +316,23 @@ impl System for GameUiManager {
                     key(*state, context, (em, cm))?;
                 }
             }
+            Ev::Event(Control {
+                event:
+                    Event::WindowEvent {
+                        window_id,
+                        event: WindowEvent::ReceivedCharacter(c),
+                    },
+                flow: _,
+            }) if *window_id == context.display.gl_window().window().id() => {
+                if let Some(player) = *self
+                    .player
+                    .get_or_init(|| Tag::new("player").find((em, cm)))
+                {
+                    if cm.get::<State>(player).map(|s| s.mode) == Some(MENU_MODE) {
+                        self.main_menu.type_char(*c);
+                    }
+                }
+            }
             Ev::Event(Control {
                 event:
                     Event::WindowEvent {
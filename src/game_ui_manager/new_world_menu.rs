@@ -0,0 +1,250 @@
+use super::MenuButton;
+use crate::{
+    chunk::GenOptions,
+    player::{SaveData, World},
+};
+use hex::{
+    anyhow::{self, Context},
+    ecs::{ComponentManager, EntityManager},
+    glium::Display,
+    math::Vec2d,
+};
+use hex_ui::ab_glyph::FontRef;
+use rand::prelude::*;
+
+pub const WORLD_SIZES: &[(&str, Option<u32>)] = &[
+    ("Infinite", None),
+    ("Small", Some(512)),
+    ("Medium", Some(2048)),
+    ("Large", Some(8192)),
+];
+pub const ORE_RICHNESS: &[(&str, f64)] = &[("Poor", 0.5), ("Normal", 1.0), ("Rich", 2.0)];
+pub const ASTEROID_DENSITY: &[(&str, f64)] = &[("Sparse", -0.25), ("Normal", 0.0), ("Dense", 0.25)];
+pub const NOISE_SCALES: &[(&str, f64)] = &[("Fine", 15.0), ("Normal", 25.0), ("Coarse", 40.0)];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Seed,
+}
+
+pub struct NewWorldMenu {
+    pub name_button: MenuButton,
+    pub seed_button: MenuButton,
+    pub random: MenuButton,
+    pub size_button: MenuButton,
+    pub richness_button: MenuButton,
+    pub density_button: MenuButton,
+    pub scale_button: MenuButton,
+    pub create: MenuButton,
+    pub back: MenuButton,
+    pub name: String,
+    pub seed: String,
+    pub focus: Field,
+    pub size: usize,
+    pub richness: usize,
+    pub density: usize,
+    pub scale: usize,
+    pub open: bool,
+    pub refresh: bool,
+}
+
+impl NewWorldMenu {
+    pub fn new(
+        display: &Display,
+        font: &FontRef<'static>,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<Self> {
+        let mut field = |position| MenuButton::new(display, font, ("", position, 4.0), (em, cm));
+        let name_button = field(Vec2d::new(-1.0, 2.2))?;
+        let seed_button = field(Vec2d::new(-1.0, 1.3))?;
+        let size_button = field(Vec2d::new(-1.0, 0.4))?;
+        let richness_button = field(Vec2d::new(-1.0, -0.5))?;
+        let density_button = field(Vec2d::new(-1.0, -1.4))?;
+        let scale_button = field(Vec2d::new(-1.0, -2.3))?;
+        let mut button =
+            |label, position| MenuButton::new(display, font, (label, position, 3.0), (em, cm));
+        let random = button("Random", Vec2d::new(2.5, 1.3))?;
+        let create = button("Create", Vec2d::new(2.5, -2.3))?;
+        let back = button("Back", Vec2d::new(2.5, -3.4))?;
+
+        Ok(Self {
+            name_button,
+            seed_button,
+            random,
+            size_button,
+            richness_button,
+            density_button,
+            scale_button,
+            create,
+            back,
+            name: String::new(),
+            seed: String::new(),
+            focus: Field::Name,
+            size: 0,
+            richness: 1,
+            density: 1,
+            scale: 1,
+            open: false,
+            refresh: true,
+        })
+    }
+
+    fn buttons(&self) -> [&MenuButton; 9] {
+        [
+            &self.name_button,
+            &self.seed_button,
+            &self.random,
+            &self.size_button,
+            &self.richness_button,
+            &self.density_button,
+            &self.scale_button,
+            &self.create,
+            &self.back,
+        ]
+    }
+
+    pub fn show(&mut self, name: &str) {
+        self.name = name.to_string();
+        self.seed = thread_rng().gen::<u32>().to_string();
+        self.focus = Field::Name;
+        self.open = true;
+        self.refresh = true;
+    }
+
+    pub fn set_active(&self, active: bool, cm: &mut ComponentManager) {
+        for b in self.buttons() {
+            b.set_active(active, cm);
+        }
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        let text = match self.focus {
+            Field::Name => &mut self.name,
+            Field::Seed => &mut self.seed,
+        };
+
+        if c == '\u{8}' {
+            text.pop();
+        } else if self.focus == Field::Seed && !c.is_ascii_digit() {
+            return;
+        } else if !c.is_control() {
+            text.push(c);
+        }
+
+        self.refresh = true;
+    }
+
+    pub fn pressed(&self, cm: &mut ComponentManager) -> [bool; 9] {
+        self.buttons().map(|b| b.pressed(cm))
+    }
+
+    pub fn handle(
+        &mut self,
+        [name, seed, random, size, richness, density, scale, create, back]: [bool; 9],
+    ) -> anyhow::Result<Option<World>> {
+        if name {
+            self.focus = Field::Name;
+        }
+
+        if seed {
+            self.focus = Field::Seed;
+        }
+
+        if random {
+            self.seed = thread_rng().gen::<u32>().to_string();
+            self.focus = Field::Seed;
+        }
+
+        if size {
+            self.size = (self.size + 1) % WORLD_SIZES.len();
+        }
+
+        if richness {
+            self.richness = (self.richness + 1) % ORE_RICHNESS.len();
+        }
+
+        if density {
+            self.density = (self.density + 1) % ASTEROID_DENSITY.len();
+        }
+
+        if scale {
+            self.scale = (self.scale + 1) % NOISE_SCALES.len();
+        }
+
+        if back {
+            self.open = false;
+        }
+
+        self.refresh |= name || seed || random || size || richness || density || scale;
+
+        if create {
+            let seed = if self.seed.is_empty() {
+                thread_rng().gen()
+            } else {
+                self.seed
+                    .parse()
+                    .with_context(|| format!("Seeds must be numbers from 0 to {}", u32::MAX))?
+            };
+            let options = GenOptions {
+                noise_scale: NOISE_SCALES[self.scale].1,
+                asteroid_density: ASTEROID_DENSITY[self.density].1,
+                ore_richness: ORE_RICHNESS[self.richness].1,
+            };
+            let world = World::create(
+                &self.name,
+                &SaveData::new(seed, WORLD_SIZES[self.size].1, options),
+            )?;
+
+            self.open = false;
+
+            return Ok(Some(world));
+        }
+
+        Ok(None)
+    }
+
+    pub fn redraw(
+        &self,
+        display: &Display,
+        font: &FontRef<'static>,
+        cm: &mut ComponentManager,
+    ) -> anyhow::Result<()> {
+        let cursor = |field| if self.focus == field { "_" } else { "" };
+        let size = match WORLD_SIZES[self.size] {
+            (label, Some(limit)) => format!("Size: {label} ({limit})"),
+            (label, None) => format!("Size: {label}"),
+        };
+
+        for (button, label) in [
+            (
+                &self.name_button,
+                format!("Name: {}{}", self.name, cursor(Field::Name)),
+            ),
+            (
+                &self.seed_button,
+                format!("Seed: {}{}", self.seed, cursor(Field::Seed)),
+            ),
+            (&self.size_button, size),
+            (
+                &self.richness_button,
+                format!("Ore: {}", ORE_RICHNESS[self.richness].0),
+            ),
+            (
+                &self.density_button,
+                format!("Asteroids: {}", ASTEROID_DENSITY[self.density].0),
+            ),
+            (
+                &self.scale_button,
+                format!("Terrain: {}", NOISE_SCALES[self.scale].0),
+            ),
+        ] {
+            button.set_label(display, font, &label, cm)?;
+        }
+
+        self.name_button.set_selected(self.focus == Field::Name, cm);
+        self.seed_button.set_selected(self.focus == Field::Seed, cm);
+
+        Ok(())
+    }
+}
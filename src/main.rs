@@ -1,13 +1,19 @@
 mod chunk;
+mod config;
 mod construct;
 mod culling_manager;
 mod game_ui_manager;
+mod map_export;
+mod migration;
 mod player;
+mod pregen;
 mod projectile;
+mod spatial;
 mod tag;
 mod util;
 
 use chunk::ChunkManager;
+use config::Config;
 use construct::ConstructManager;
 use culling_manager::CullingManager;
 use game_ui_manager::GameUiManager;
@@ -25,17 +31,22 @@ use hex::{
 use hex_instance::InstanceRenderer;
 use hex_physics::PhysicsManager;
 use hex_ui::{UiManager, UiRenderer};
+use map_export::MapExport;
 use player::PlayerManager;
+use pregen::Pregen;
 use projectile::ProjectileManager;
-use std::time::Duration;
+use spatial::SpatialManager;
+use std::{env, time::Duration};
 use tag::Tag;
 
-const SAVE_DIR: &str = "save";
+const SAVE_DIR: &str = "saves";
+const ASSETS_DIR: &str = "assets";
 const UI_CAM_DIMS: f32 = 10.0;
 const PHYSICS_CYCLES: u32 = 1;
 const PHYSICS_RATE: u32 = 3;
 const PROJECTILE_LAYER: Id = 1;
 const PLAYER_LAYER: Id = 2;
+const ASTEROID_LAYER: Id = 3;
 
 fn main() {
     init().unwrap();
@@ -44,6 +55,15 @@ fn main() {
 fn init() -> anyhow::Result<()> {
     util::setup_directories()?;
 
+    let mut args = env::args().skip(1);
+
+    match args.next().as_deref() {
+        Some("export") => return MapExport::run(args),
+        Some("pregen") => return Pregen::parse(args)?.run(),
+        Some(arg) => anyhow::bail!("Unknown argument \"{arg}\""),
+        None => {}
+    }
+
     let (window_x, window_y) = {
         let window_dims_x = 1920;
         let window_dims_y = 1080;
@@ -78,7 +98,8 @@ fn init() -> anyhow::Result<()> {
         PHYSICS_CYCLES,
         Some(Duration::from_secs_f32(1.0 / 30.0)),
     ));
-    system_manager.add(ChunkManager::new((&mut em, &mut cm)));
+    system_manager.add(ChunkManager::new(Config::load()?, (&mut em, &mut cm)));
+    system_manager.add(SpatialManager::new((&mut em, &mut cm)));
     system_manager.add(PlayerManager::new(
         &context,
         (window_x, window_y),
@@ -0,0 +1,173 @@
+use crate::{
+    chunk::{Region, TileManifest, CHUNK_SIZE},
+    construct::ConstructData,
+    player::{world::DEFAULT_WORLD, SaveData, World},
+};
+use hex::anyhow;
+use image::{Rgba, RgbaImage};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const EXPORT_SCALE: u32 = 2;
+pub const MAX_EXPORT_SIZE: u32 = 8192;
+pub const SPACE_COLOR: Rgba<u8> = Rgba([16, 16, 24, 255]);
+pub const UNKNOWN_COLOR: Rgba<u8> = Rgba([255, 0, 255, 255]);
+pub const CONSTRUCT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+pub const PLAYER_COLOR: Rgba<u8> = Rgba([255, 64, 64, 255]);
+
+pub struct MapExport {
+    pub colors: HashMap<String, Rgba<u8>>,
+}
+
+impl MapExport {
+    pub fn load() -> anyhow::Result<Self> {
+        let mut colors = HashMap::new();
+
+        for manifest in TileManifest::load_all()? {
+            let [r, g, b] = manifest.color()?;
+
+            colors.insert(manifest.id, Rgba([r, g, b, 255]));
+        }
+
+        Ok(Self { colors })
+    }
+
+    pub fn path(world: &World) -> PathBuf {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|t| t.as_secs())
+            .unwrap_or_default();
+
+        world.dir().join(format!("map_{time}.png"))
+    }
+
+    pub fn run<I>(args: I) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let world = World::new(&args.next().unwrap_or_else(|| DEFAULT_WORLD.to_string()))?;
+        let save_data = SaveData::load(&world)?;
+        let path = args
+            .next()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| Self::path(&world));
+
+        Self::load()?.export(
+            &world,
+            &save_data.constructs,
+            Some(save_data.player_position),
+            &path,
+        )?;
+
+        println!("Exported map to {}", path.display());
+
+        Ok(())
+    }
+
+    pub fn export(
+        &self,
+        world: &World,
+        constructs: &[ConstructData],
+        player: Option<[f32; 2]>,
+        path: &Path,
+    ) -> anyhow::Result<()> {
+        let dir = world.regions_dir();
+        let regions = Region::saved(&dir)?;
+        let mut bounds: Option<((i32, i32), (i32, i32))> = None;
+
+        for pos in &regions {
+            for (x, y) in Region::load(&dir, *pos)?.chunks.keys() {
+                let (min, max) = bounds.unwrap_or(((*x, *y), (*x, *y)));
+
+                bounds = Some((
+                    (min.0.min(*x), min.1.min(*y)),
+                    (max.0.max(*x), max.1.max(*y)),
+                ));
+            }
+        }
+
+        let Some((min, max)) = bounds else {
+            anyhow::bail!("There are no saved chunks to export");
+        };
+        let size = CHUNK_SIZE as i32;
+        let origin = ((min.0 - 1) * size, max.1 * size);
+        let (width, height) = (
+            (max.0 - min.0 + 1) as u64 * (CHUNK_SIZE * EXPORT_SCALE) as u64,
+            (max.1 - min.1 + 1) as u64 * (CHUNK_SIZE * EXPORT_SCALE) as u64,
+        );
+
+        if width > MAX_EXPORT_SIZE as u64 || height > MAX_EXPORT_SIZE as u64 {
+            anyhow::bail!(
+                "The explored map spans {width}x{height} pixels, which is larger than the \
+                 {MAX_EXPORT_SIZE}x{MAX_EXPORT_SIZE} export limit"
+            );
+        }
+
+        let mut img = RgbaImage::new(width as u32, height as u32);
+
+        for pos in regions {
+            let region = Region::load(&dir, pos)?;
+
+            for (x, y) in region.chunks.keys() {
+                let Some(data) = region.get((*x, *y)) else {
+                    continue;
+                };
+
+                for (i, column) in data.grid.iter().enumerate() {
+                    for (j, tile) in column.iter().enumerate() {
+                        let color = tile
+                            .as_ref()
+                            .map(|t| self.colors.get(t).cloned().unwrap_or(UNKNOWN_COLOR))
+                            .unwrap_or(SPACE_COLOR);
+
+                        Self::fill(
+                            &mut img,
+                            origin,
+                            ((x - 1) * size + i as i32, (y - 1) * size + j as i32),
+                            0,
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+
+        for c in constructs {
+            Self::fill(&mut img, origin, Self::tile(c.position), 0, CONSTRUCT_COLOR);
+        }
+
+        if let Some(player) = player {
+            Self::fill(&mut img, origin, Self::tile(player), 1, PLAYER_COLOR);
+        }
+
+        img.save(path)?;
+
+        Ok(())
+    }
+
+    fn tile([x, y]: [f32; 2]) -> (i32, i32) {
+        (x.floor() as i32, y.floor() as i32)
+    }
+
+    fn fill(
+        img: &mut RgbaImage,
+        (ox, oy): (i32, i32),
+        (x, y): (i32, i32),
+        radius: i32,
+        color: Rgba<u8>,
+    ) {
+        let scale = EXPORT_SCALE as i32;
+
+        for i in (x - radius - ox) * scale..(x + radius + 1 - ox) * scale {
+            for j in (oy - y - radius - 1) * scale..(oy - y + radius) * scale {
+                if i >= 0 && j >= 0 && (i as u32) < img.width() && (j as u32) < img.height() {
+                    img.put_pixel(i as u32, j as u32, color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use hex::anyhow::{self, Context};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+pub type Migration = fn(&mut Value) -> anyhow::Result<()>;
+
+pub fn migrate<T>(mut value: Value, migrations: &[Migration]) -> anyhow::Result<T>
+where
+    T: DeserializeOwned,
+{
+    let current = migrations.len() as u64;
+    let version = match value.get("version") {
+        Some(v) => v.as_u64().with_context(|| format!("Invalid version {v}"))?,
+        None => 0,
+    };
+
+    if version > current {
+        anyhow::bail!(
+            "Version {version} was saved by a newer game, this build supports up to version {current}"
+        );
+    }
+
+    for (i, migration) in migrations.iter().enumerate().skip(version as usize) {
+        migration(&mut value)
+            .with_context(|| format!("Failed to migrate from version {i} to {}", i + 1))?;
+    }
+
+    value
+        .as_object_mut()
+        .context("Expected an object")?
+        .insert("version".to_string(), current.into());
+
+    Ok(serde_json::from_value(value)?)
+}
+
+pub fn insert_default(value: &mut Value, key: &str, default: Value) -> anyhow::Result<()> {
+    value
+        .as_object_mut()
+        .context("Expected an object")?
+        .entry(key)
+        .or_insert(default);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn add_a(value: &mut Value) -> anyhow::Result<()> {
+        insert_default(value, "a", 1.into())
+    }
+
+    fn add_b(value: &mut Value) -> anyhow::Result<()> {
+        insert_default(value, "b", 2.into())
+    }
+
+    #[test]
+    fn migrate_runs_pending_migrations() -> anyhow::Result<()> {
+        let value: Value = migrate(json!({ "version": 1, "a": 5 }), &[add_a, add_b])?;
+
+        assert_eq!(value, json!({ "version": 2, "a": 5, "b": 2 }));
+
+        let value: Value = migrate(json!({}), &[add_a, add_b])?;
+
+        assert_eq!(value, json!({ "version": 2, "a": 1, "b": 2 }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_rejects_newer_versions() {
+        assert!(migrate::<Value>(json!({ "version": 3 }), &[add_a, add_b]).is_err());
+        assert!(migrate::<Value>(json!({ "version": "1" }), &[add_a]).is_err());
+    }
+}
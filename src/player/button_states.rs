@@ -6,5 +6,6 @@ pub struct ButtonStates {
     pub right: bool,
     pub firing: bool,
     pub removing: bool,
+    pub interacting: bool,
     pub mode: usize,
 }
@@ -2,27 +2,29 @@ pub mod button_states;
 pub mod player_manager;
 pub mod save_data;
 pub mod state;
+pub mod world;
 
 pub use button_states::ButtonStates;
 pub use hex_instance::Instance;
 pub use player_manager::PlayerManager;
 pub use save_data::SaveData;
 pub use state::State;
+pub use world::World;
 
-use crate::{
-    construct::{FURNACE, LEFT_ROUTER, LEFT_SPLITTER, MINER, RIGHT_ROUTER, RIGHT_SPLITTER},
-    projectile::Projectile,
-};
+use crate::{chunk::tile::ASTEROID_1, projectile::Projectile};
 use hex::{
     anyhow,
-    ecs::{component_manager::Component, Context},
+    ecs::{component_manager::Component, Context, Id},
     math::Vec2d,
 };
 use hex_physics::Collider;
-use std::time::Instant;
+use std::{iter, time::Instant};
 
 pub const HOTBAR_SLOTS: usize = 10;
 pub const PLAYER_MOVE_SPEED: f32 = 10.0;
+pub const PLAYER_RADIUS: f32 = 1.0 / 6.0;
+pub const TILE_TOOL: &str = "tile_tool";
+pub const PLACED_TILE: &str = ASTEROID_1;
 
 #[derive(Clone)]
 pub struct Player {
@@ -32,17 +34,19 @@ pub struct Player {
     pub states: ButtonStates,
     pub projectile: (Projectile, Collider, Instance),
     pub hotbar: Vec<Option<String>>,
+    pub hovered: Option<Id>,
 }
 
 impl Player {
-    pub fn new(context: &Context) -> anyhow::Result<Self> {
+    pub fn new(context: &Context, constructs: &[String]) -> anyhow::Result<Self> {
         Ok(Self {
             health: 25.0,
             fire_time: Instant::now(),
             trail_time: Instant::now(),
             states: Default::default(),
             projectile: Projectile::player_bullet(context)?,
-            hotbar: Self::default_hotbar(),
+            hotbar: Self::default_hotbar(constructs),
+            hovered: None,
         })
     }
 
@@ -50,17 +54,12 @@ impl Player {
         self.hotbar.get(self.states.mode).cloned().flatten()
     }
 
-    pub fn default_hotbar() -> Vec<Option<String>> {
-        let mut hotbar = vec![None; HOTBAR_SLOTS];
-
-        hotbar[1] = Some(MINER.to_string());
-        hotbar[2] = Some(RIGHT_ROUTER.to_string());
-        hotbar[3] = Some(LEFT_ROUTER.to_string());
-        hotbar[4] = Some(FURNACE.to_string());
-        hotbar[5] = Some(LEFT_SPLITTER.to_string());
-        hotbar[6] = Some(RIGHT_SPLITTER.to_string());
-
-        hotbar
+    pub fn default_hotbar(constructs: &[String]) -> Vec<Option<String>> {
+        iter::once(None)
+            .chain(constructs.iter().cloned().map(Some))
+            .chain(iter::repeat(None))
+            .take(HOTBAR_SLOTS.max(constructs.len() + 1))
+            .collect()
     }
 
     pub fn force(&self) -> Vec2d {
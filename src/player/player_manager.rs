@@ -1,12 +1,13 @@
 use super::{state::GAME_MODE, Player, State};
 use crate::{
-    chunk::{chunk_manager::MAX_MAP_SIZE, CHUNK_SIZE},
+    chunk::{Chunk, ChunkManager, Map, TileCollider},
     construct::Construct,
-    player::PLAYER_MOVE_SPEED,
+    player::{PLACED_TILE, PLAYER_MOVE_SPEED, PLAYER_RADIUS, TILE_TOOL},
+    spatial::SpatialIndex,
     util, Tag, PLAYER_LAYER, PROJECTILE_LAYER, UI_CAM_DIMS,
 };
 use hex::{
-    anyhow,
+    anyhow::{self, Context as _},
     assets::Shape,
     components::{Camera, Sprite, Transform},
     ecs::{
@@ -23,7 +24,10 @@ use hex::{
 use hex_instance::Instance;
 use hex_physics::{Collider, Physical};
 use hex_ui::ScreenTransform;
-use std::time::{Duration, Instant};
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
 
 pub const CAM_DIMS: f32 = 50.0 / 3.0;
 
@@ -32,6 +36,9 @@ pub struct PlayerManager {
     camera: Id,
     crosshair: Id,
     prefab: Id,
+    selected: Option<String>,
+    map: Id,
+    index: Id,
     mouse_pos: (f64, f64),
     window_dims: (u32, u32),
     frame: Instant,
@@ -47,6 +54,12 @@ impl PlayerManager {
         (window_x, window_y): (i32, i32),
         (em, cm): (&mut EntityManager, &mut ComponentManager),
     ) -> anyhow::Result<Self> {
+        let map = Tag::new("map")
+            .find((em, cm))
+            .context("The map has not been created")?;
+        let index = Tag::new("spatial_index")
+            .find((em, cm))
+            .context("The spatial index has not been created")?;
         let player = em.add();
 
         cm.add(player, Tag::new("player"), em);
@@ -64,7 +77,7 @@ impl PlayerManager {
             em,
         );
 
-        let p = Player::new(context)?;
+        let p = Player::new(context, &state.hotbar)?;
 
         cm.add(player, p, em);
         cm.add(
@@ -86,7 +99,7 @@ impl PlayerManager {
         cm.add(
             player,
             Collider::oct(
-                Vec2d([1.0 / 3.0; 2]),
+                Vec2d([PLAYER_RADIUS * 2.0; 2]),
                 vec![PLAYER_LAYER],
                 vec![PROJECTILE_LAYER],
                 false,
@@ -148,6 +161,9 @@ impl PlayerManager {
             player,
             crosshair,
             prefab,
+            selected: None,
+            map,
+            index,
             frame: Instant::now(),
             mouse_pos: Default::default(),
             window_dims: Default::default(),
@@ -164,12 +180,57 @@ impl PlayerManager {
             + Vec2d([0.5; 2])
     }
 
-    pub fn update_hotbar(
-        &mut self,
-        (em, cm): (&mut EntityManager, &mut ComponentManager),
-    ) -> anyhow::Result<()> {
-        if let Some(mouse_pos) = cm
-            .get::<Camera>(self.camera)
+    pub fn resolve_collisions(&self, cm: &mut ComponentManager) {
+        let collisions: Vec<_> = cm
+            .get::<Collider>(self.player)
+            .map(|c| {
+                c.collisions
+                    .iter()
+                    .filter_map(|e| {
+                        Some((
+                            cm.get::<Transform>(*e)?.position(),
+                            cm.get::<TileCollider>(*e)?.dims,
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for (position, dims) in collisions {
+            if let Some(transform) = cm.get_mut::<Transform>(self.player) {
+                let delta = transform.position() - position;
+                let overlap = Vec2d::new(
+                    dims.x() / 2.0 + PLAYER_RADIUS - delta.x().abs(),
+                    dims.y() / 2.0 + PLAYER_RADIUS - delta.y().abs(),
+                );
+
+                if overlap.x() <= 0.0 || overlap.y() <= 0.0 {
+                    continue;
+                }
+
+                let push = if overlap.x() < overlap.y() {
+                    Vec2d::new(overlap.x().copysign(delta.x()), 0.0)
+                } else {
+                    Vec2d::new(0.0, overlap.y().copysign(delta.y()))
+                };
+
+                transform.set_position(transform.position() + push);
+
+                if let Some(physical) = cm.get_mut::<Physical>(self.player) {
+                    if push.x() * physical.force.x() < 0.0 {
+                        physical.force = Vec2d::new(0.0, physical.force.y());
+                    }
+
+                    if push.y() * physical.force.y() < 0.0 {
+                        physical.force = Vec2d::new(physical.force.x(), 0.0);
+                    }
+                }
+            }
+        }
+    }
+
+    fn mouse_pos(&self, cm: &ComponentManager) -> Option<Vec2d> {
+        cm.get::<Camera>(self.camera)
             .map(|camera| camera.dimensions().0)
             .and_then(|p| {
                 util::mouse_pos_world(
@@ -179,7 +240,46 @@ impl PlayerManager {
                     self.mouse_pos,
                 )
             })
-        {
+    }
+
+    pub fn update_preview(&mut self, (em, cm): (&mut EntityManager, &mut ComponentManager)) {
+        let selected = cm.get::<Player>(self.player).and_then(|p| p.current_item());
+
+        if selected == self.selected {
+            return;
+        }
+
+        let instance = cm
+            .get::<State>(self.player)
+            .and_then(|s| match selected.as_deref()? {
+                TILE_TOOL => Some(Instance::new(
+                    s.tiles.get(PLACED_TILE)?.texture.clone(),
+                    [1.0, 1.0, 1.0, 0.5],
+                    -2.9,
+                    true,
+                )),
+                id => s.constructs.get(id).map(|(_, i)| {
+                    let mut i = i.clone();
+
+                    i.z += 0.1;
+
+                    i
+                }),
+            });
+
+        match instance {
+            Some(instance) => cm.add(self.prefab, instance, em),
+            None => cm.rm::<Instance>(self.prefab, em),
+        }
+
+        self.selected = selected;
+    }
+
+    pub fn update_hotbar(
+        &mut self,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        if let Some(mouse_pos) = self.mouse_pos(cm) {
             if let Some(((c, firing, removing), player_pos)) =
                 cm.get::<Player>(self.player).cloned().and_then(|t| {
                     Some((
@@ -193,14 +293,6 @@ impl PlayerManager {
                     ))
                 })
             {
-                if let Some(mut i) = c.as_ref().map(|(_, i)| i.clone()) {
-                    i.z += 0.1;
-
-                    cm.add(self.prefab, i, em);
-                } else {
-                    cm.rm::<Instance>(self.prefab, em);
-                }
-
                 if let Some(screen_pos) = cm.get::<ScreenTransform>(self.crosshair).map(|st| {
                     Vec2d::new(
                         st.position.x() / self.window_x,
@@ -225,32 +317,47 @@ impl PlayerManager {
                         let position = Self::tile_pos(mouse_pos, player_pos);
                         let pos = position + player_pos;
 
-                        if pos.x() >= 0.0
-                            && pos.x() <= MAX_MAP_SIZE as f32
-                            && pos.y() >= 0.0
-                            && pos.y() <= MAX_MAP_SIZE as f32
-                        {
-                            let x = pos.x() as u64;
-                            let y = pos.y() as u64;
+                        if util::in_bounds(
+                            cm.get::<State>(self.player)
+                                .and_then(|s| s.save_data.size_limit),
+                            pos,
+                        ) {
+                            let tile = SpatialIndex::tile_pos(pos);
 
                             if let Some(transform) = cm.get_mut::<Transform>(self.prefab) {
                                 transform.set_position(pos);
                             }
 
-                            let space = em.entities().find(|e| {
-                                cm.get::<Construct>(*e).is_some()
-                                    && cm
-                                        .get::<Transform>(*e)
-                                        .map(|t| {
-                                            t.position().x().floor() as u64 == x
-                                                && t.position().y().floor() as u64 == y
-                                        })
-                                        .unwrap_or(false)
-                            });
+                            let space = cm
+                                .get::<SpatialIndex>(self.index)
+                                .map(|i| i.tile(tile))
+                                .unwrap_or_default()
+                                .into_iter()
+                                .find(|e| {
+                                    cm.get::<Construct>(*e).is_some()
+                                        && cm
+                                            .get::<Transform>(*e)
+                                            .map(|t| SpatialIndex::tile_pos(t.position()) == tile)
+                                            .unwrap_or(false)
+                                });
 
                             if let Some(e) = space {
                                 if removing {
+                                    let sprites: Vec<_> = cm
+                                        .get::<Construct>(e)
+                                        .and_then(|c| c.belt.as_ref())
+                                        .map(|b| b.items.iter().filter_map(|i| i.entity).collect())
+                                        .unwrap_or_default();
+
+                                    for sprite in sprites {
+                                        em.rm(sprite, cm);
+                                    }
+
                                     em.rm(e, cm);
+
+                                    if let Some(index) = cm.get_mut::<SpatialIndex>(self.index) {
+                                        index.remove(e);
+                                    }
                                 }
                             } else if firing {
                                 let construct = em.add();
@@ -262,6 +369,10 @@ impl PlayerManager {
                                 );
                                 cm.add(construct, c.clone(), em);
                                 cm.add(construct, i.clone(), em);
+
+                                if let Some(index) = cm.get_mut::<SpatialIndex>(self.index) {
+                                    index.insert(construct, pos);
+                                }
                             }
                         }
                     }
@@ -271,6 +382,127 @@ impl PlayerManager {
 
         Ok(())
     }
+
+    pub fn interact(&mut self, cm: &mut ComponentManager) {
+        let hovered = self
+            .mouse_pos(cm)
+            .zip(cm.get::<Transform>(self.player).map(|t| t.position()))
+            .map(|(mouse_pos, player_pos)| {
+                SpatialIndex::tile_pos(Self::tile_pos(mouse_pos, player_pos) + player_pos)
+            })
+            .and_then(|tile| {
+                cm.get::<SpatialIndex>(self.index)?
+                    .tile(tile)
+                    .into_iter()
+                    .find(|e| cm.get::<Construct>(*e).is_some())
+            });
+        let interacting = cm
+            .get_mut::<Player>(self.player)
+            .map(|p| {
+                p.hovered = hovered;
+
+                mem::take(&mut p.states.interacting)
+            })
+            .unwrap_or_default();
+
+        if let Some((e, interact)) = hovered
+            .filter(|_| interacting)
+            .and_then(|e| Some((e, cm.get::<Construct>(e)?.interact.clone()?)))
+        {
+            (*interact)(e, cm);
+        }
+    }
+
+    pub fn edit_tiles(&mut self, (em, cm): (&mut EntityManager, &mut ComponentManager)) {
+        let Some(((firing, removing), player_pos, mouse_pos)) = cm
+            .get::<Player>(self.player)
+            .filter(|p| p.current_item().as_deref() == Some(TILE_TOOL))
+            .map(|p| (p.states.firing, p.states.removing))
+            .and_then(|states| {
+                Some((
+                    states,
+                    cm.get::<Transform>(self.player)?.position(),
+                    self.mouse_pos(cm)?,
+                ))
+            })
+        else {
+            return;
+        };
+        let pos = Self::tile_pos(mouse_pos, player_pos) + player_pos;
+
+        if let Some(size_limit) = cm.get::<State>(self.player).map(|s| s.save_data.size_limit) {
+            if let Some(transform) = cm.get_mut::<Transform>(self.prefab) {
+                transform.set_position(pos);
+            }
+
+            if !util::in_bounds(size_limit, pos) {
+                return;
+            }
+        }
+
+        let Some(chunk) = cm
+            .get::<Map>(self.map)
+            .and_then(|m| m.loaded.get(&ChunkManager::chunk_pos(pos)).cloned())
+        else {
+            return;
+        };
+        let occupied = cm
+            .get::<SpatialIndex>(self.index)
+            .map(|i| i.tile(SpatialIndex::tile_pos(pos)))
+            .unwrap_or_default()
+            .into_iter()
+            .any(|e| cm.get::<Construct>(e).is_some());
+
+        if occupied {
+            return;
+        }
+
+        let index = ChunkManager::tile_index(pos);
+        let delta = player_pos - pos;
+        let blocked =
+            delta.x().abs() < 0.5 + PLAYER_RADIUS && delta.y().abs() < 0.5 + PLAYER_RADIUS;
+        let edited = if firing && !blocked {
+            let material = cm
+                .get::<State>(self.player)
+                .map(|s| s.save_data.material)
+                .unwrap_or_default();
+
+            (material > 0
+                && cm
+                    .get_mut::<Chunk>(chunk)
+                    .map(|c| c.place(index, PLACED_TILE))
+                    .unwrap_or_default())
+            .then(|| {
+                if let Some(state) = cm.get_mut::<State>(self.player) {
+                    state.save_data.material -= 1;
+                }
+
+                cm.get::<State>(self.player)
+                    .and_then(|s| Some(s.tiles.get(PLACED_TILE)?.texture.clone()))
+            })
+            .flatten()
+        } else if removing {
+            cm.get_mut::<Chunk>(chunk)
+                .and_then(|c| c.dig(index))
+                .and_then(|_| {
+                    let state = cm.get_mut::<State>(self.player)?;
+
+                    state.save_data.material += 1;
+
+                    Some(state.space.clone())
+                })
+        } else {
+            None
+        };
+
+        if let Some(tile) = edited {
+            if let Some(texture) = cm.get::<Instance>(chunk).map(|i| i.texture.clone()) {
+                ChunkManager::blit_tile(&tile, &texture, index);
+            }
+
+            ChunkManager::build_colliders(chunk, self.player, (em, cm));
+        }
+    }
 }
 
 impl System for PlayerManager {
@@ -420,44 +652,31 @@ impl System for PlayerManager {
                             cm.add(p, transform.clone(), em);
                         }
 
+                        self.resolve_collisions(cm);
+
+                        let size_limit = cm
+                            .get::<State>(self.player)
+                            .and_then(|s| s.save_data.size_limit);
+
                         if let Some(pos) = if let Some(t) = cm
                             .get_mut::<Transform>(self.player)
                             .and_then(|t| t.active.then_some(t))
                         {
-                            let position = t.position();
-
-                            t.set_position(Vec2d::new(
-                                position.x().clamp(
-                                    CHUNK_SIZE as f32,
-                                    MAX_MAP_SIZE as f32 - CHUNK_SIZE as f32,
-                                ),
-                                position.y().clamp(
-                                    CHUNK_SIZE as f32,
-                                    MAX_MAP_SIZE as f32 - CHUNK_SIZE as f32,
-                                ),
-                            ));
+                            t.set_position(util::clamp_bounds(size_limit, t.position()));
 
                             Some(t.position())
                         } else {
                             None
                         } {
                             if let Some(ct) = cm.get_mut::<Transform>(self.camera) {
-                                let position = Vec2d::new(
-                                    pos.x().clamp(
-                                        CHUNK_SIZE as f32,
-                                        MAX_MAP_SIZE as f32 - CHUNK_SIZE as f32,
-                                    ),
-                                    pos.y().clamp(
-                                        CHUNK_SIZE as f32,
-                                        MAX_MAP_SIZE as f32 - CHUNK_SIZE as f32,
-                                    ),
-                                );
-
-                                ct.set_position(position);
+                                ct.set_position(pos);
                             }
                         }
 
+                        self.update_preview((em, cm));
                         self.update_hotbar((em, cm))?;
+                        self.edit_tiles((em, cm));
+                        self.interact(cm);
                     }
                 }
             }
@@ -1,28 +1,129 @@
-use crate::construct::{ConstructData, ItemData};
+use super::World;
+use crate::{
+    chunk::GenOptions,
+    construct::{ConstructData, ItemData},
+    migration::{self, Migration},
+    util,
+};
+use hex::anyhow::{self, Context};
 use rand::prelude::*;
 use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+
+pub const SAVE_MIGRATIONS: &[Migration] = &[SaveData::migrate_v0];
+pub const SPAWN_RANGE: u32 = 1000;
+pub const SAVE_VERSION: u32 = SAVE_MIGRATIONS.len() as u32;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SaveData {
+    pub version: u32,
     pub seed: u32,
     pub player_position: [f32; 2],
     pub player_velocity: [f32; 2],
     pub constructs: Vec<ConstructData>,
     pub items: Vec<ItemData>,
+    pub size_limit: Option<u32>,
+    pub options: GenOptions,
+    pub material: u32,
 }
 
 impl SaveData {
-    pub fn new(seed: u32, rng: &mut StdRng) -> Self {
+    pub fn new(seed: u32, size_limit: Option<u32>, options: GenOptions) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let range = size_limit.map_or(SPAWN_RANGE, |l| l.min(SPAWN_RANGE));
+
         Self {
+            version: SAVE_VERSION,
             seed,
-            player_position: [Self::gen_map_coord(rng), Self::gen_map_coord(rng)],
+            player_position: [
+                Self::gen_map_coord(&mut rng, range),
+                Self::gen_map_coord(&mut rng, range),
+            ],
             player_velocity: [0.0; 2],
             constructs: Vec::new(),
             items: Vec::new(),
+            size_limit,
+            options,
+            material: 0,
+        }
+    }
+
+    pub fn load(world: &World) -> anyhow::Result<Self> {
+        let path = world.save_path();
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        Self::parse(&content).with_context(|| format!("Failed to load save {}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        migration::migrate(serde_json::from_str(content)?, SAVE_MIGRATIONS)
+    }
+
+    fn migrate_v0(value: &mut Value) -> anyhow::Result<()> {
+        migration::insert_default(value, "size_limit", Value::Null)?;
+        migration::insert_default(
+            value,
+            "options",
+            serde_json::to_value(GenOptions::default())?,
+        )?;
+        migration::insert_default(value, "material", 0.into())?;
+
+        if let Some(constructs) = value.get_mut("constructs").and_then(|c| c.as_array_mut()) {
+            for construct in constructs {
+                for key in ["crafter", "inventory", "belt", "storage", "power", "filter"] {
+                    migration::insert_default(construct, key, Value::Null)?;
+                }
+            }
         }
+
+        Ok(())
     }
 
-    pub fn gen_map_coord(rng: &mut StdRng) -> f32 {
-        rng.gen_range(0..1000) as f32
+    pub fn save(&self, world: &World) -> anyhow::Result<()> {
+        util::write_atomic(&world.save_path(), serde_json::to_string(self)?.as_bytes())
+    }
+
+    pub fn gen_map_coord(rng: &mut StdRng, range: u32) -> f32 {
+        rng.gen_range(0..range.max(1)) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_migrates_version_0() -> anyhow::Result<()> {
+        let save = SaveData::parse(
+            r#"{
+                "seed": 7,
+                "player_position": [1.0, 2.0],
+                "player_velocity": [0.0, 0.0],
+                "constructs": [
+                    { "id": "miner", "position": [0.5, 0.5], "rotation": 0.0, "mode": null, "tick_amount": 3 }
+                ],
+                "items": []
+            }"#,
+        )?;
+
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.seed, 7);
+        assert_eq!(save.size_limit, None);
+        assert_eq!(save.material, 0);
+        assert_eq!(save.constructs.len(), 1);
+        assert_eq!(save.constructs[0].tick_amount, 3);
+        assert!(save.constructs[0].crafter.is_none());
+        assert!(save.constructs[0].inventory.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_rejects_newer_versions() {
+        let save = SaveData::parse(&format!(r#"{{ "version": {} }}"#, SAVE_VERSION + 1));
+
+        assert!(save.is_err());
     }
 }
@@ -1,5 +1,9 @@
-use super::SaveData;
-use crate::{chunk::Tile, construct::Construct, construct::Item, SAVE_DIR};
+use super::{SaveData, World, PLACED_TILE, TILE_TOOL};
+use crate::{
+    chunk::{Generator, Tile, TileManifest},
+    construct::{Construct, ConstructManifest, Item, ItemManifest, PowerKind, Recipe, MACHINES},
+    util,
+};
 use hex::{
     anyhow,
     assets::Texture,
@@ -10,24 +14,28 @@ use hex::{
     },
 };
 use hex_instance::Instance;
-use noise::Perlin;
-use once_cell::sync::Lazy;
-use rand::prelude::*;
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{collections::HashMap, sync::Arc};
 
-pub static SAVE_PATH: Lazy<PathBuf> = Lazy::new(|| PathBuf::from(SAVE_DIR).join("map.json"));
+pub const TILES_MANIFEST: &str = "tiles.json";
+pub const ITEMS_MANIFEST: &str = "items.json";
+pub const CONSTRUCTS_MANIFEST: &str = "constructs.json";
+pub const STRUCTURES_MANIFEST: &str = "structures.json";
+pub const BIOMES_MANIFEST: &str = "biomes.json";
+pub const RECIPES_MANIFEST: &str = "recipes.json";
 
 pub const MENU_MODE: u32 = 0;
 pub const GAME_MODE: u32 = 1;
 
 #[derive(Clone)]
 pub struct State {
+    pub world: Option<World>,
     pub save_data: SaveData,
-    pub rng: StdRng,
-    pub perlin: Perlin,
+    pub generator: Arc<Generator>,
     pub tiles: HashMap<String, Tile>,
     pub items: HashMap<String, (Item, Instance)>,
     pub constructs: HashMap<String, (Construct, Instance)>,
+    pub recipes: HashMap<String, Recipe>,
+    pub hotbar: Vec<String>,
     pub space: Texture,
     pub mode: u32,
 }
@@ -37,61 +45,135 @@ impl State {
         context: &Context,
         (em, cm): (&mut EntityManager, &mut ComponentManager),
     ) -> anyhow::Result<Self> {
-        let (rng, save_data) = fs::read_to_string(&*SAVE_PATH)
-            .ok()
-            .map(|s| -> anyhow::Result<_> {
-                let save_data: SaveData = serde_json::from_str(&s)?;
-
-                Ok((StdRng::seed_from_u64(save_data.seed as u64), save_data))
-            })
-            .unwrap_or_else(|| {
-                let seed = thread_rng().gen_range(u32::MIN..u32::MAX);
-                let mut rng = StdRng::seed_from_u64(seed as u64);
-                let data = SaveData::new(seed, &mut rng);
-
-                Ok((rng, data))
-            })?;
-        let perlin = Perlin::new(save_data.seed);
+        let save_data = SaveData::new(0, None, Default::default());
+        let mut tiles = HashMap::new();
+
+        for manifest in TileManifest::load_all()? {
+            let tile = Tile::load(context, manifest)?;
+
+            tiles.insert(tile.id.clone(), tile);
+        }
+
+        let mut items = HashMap::new();
+
+        for manifest in util::load_manifest::<ItemManifest>(ITEMS_MANIFEST)? {
+            let item = Item::load(context, manifest)?;
+
+            if let Some((item, _)) = items.insert(item.0.id.clone(), item) {
+                anyhow::bail!("Item \"{}\" is defined more than once", item.id);
+            }
+        }
+
+        let mut recipes = HashMap::new();
+
+        for recipe in util::load_manifest::<Recipe>(RECIPES_MANIFEST)? {
+            recipe.validate(MACHINES)?;
+
+            for stack in recipe.inputs.iter().chain(&recipe.outputs) {
+                if !items.contains_key(&stack.item) {
+                    anyhow::bail!(
+                        "Recipe \"{}\" uses item \"{}\", which is not defined",
+                        recipe.id,
+                        stack.item
+                    );
+                }
+            }
+
+            if let Some(recipe) = recipes.insert(recipe.id.clone(), recipe) {
+                anyhow::bail!("Recipe \"{}\" is defined more than once", recipe.id);
+            }
+        }
+
+        let mut constructs = HashMap::new();
+        let mut hotbar = Vec::new();
+
+        for manifest in util::load_manifest::<ConstructManifest>(CONSTRUCTS_MANIFEST)? {
+            if let Some(construct) = Construct::load(context, manifest, (em, cm))? {
+                if let Some(PowerKind::Generator {
+                    fuel: Some(fuel), ..
+                }) = construct.0.power.as_ref().map(|p| &p.kind)
+                {
+                    if !items.contains_key(fuel) {
+                        anyhow::bail!(
+                            "Construct \"{}\" burns item \"{fuel}\", which is not defined",
+                            construct.0.id
+                        );
+                    }
+                }
+
+                for item in construct.0.filter.iter().flatten() {
+                    if !items.contains_key(item) {
+                        anyhow::bail!(
+                            "Construct \"{}\" filters item \"{item}\", which is not defined",
+                            construct.0.id
+                        );
+                    }
+                }
+
+                hotbar.push(construct.0.id.clone());
+
+                if let Some((construct, _)) = constructs.insert(construct.0.id.clone(), construct) {
+                    anyhow::bail!("Construct \"{}\" is defined more than once", construct.id);
+                }
+            }
+        }
+
+        if !tiles.contains_key(PLACED_TILE) {
+            anyhow::bail!("Tile \"{PLACED_TILE}\" is placed by the tile tool but is not defined");
+        }
+
+        hotbar.push(TILE_TOOL.to_string());
+
+        let generator = Arc::new(Generator::load(save_data.seed, save_data.options)?);
+
+        for structure in &generator.structures {
+            for c in &structure.constructs {
+                if !constructs.contains_key(&c.id) {
+                    anyhow::bail!(
+                        "Structure \"{}\" uses construct \"{}\", which is not defined",
+                        structure.id,
+                        c.id
+                    );
+                }
+            }
+
+            for i in &structure.items {
+                if !items.contains_key(&i.id) {
+                    anyhow::bail!(
+                        "Structure \"{}\" uses item \"{}\", which is not defined",
+                        structure.id,
+                        i.id
+                    );
+                }
+            }
+        }
 
         Ok(Self {
+            world: None,
             save_data,
-            perlin,
-            rng,
-            tiles: vec![
-                Tile::asteroid_1(context)?,
-                Tile::asteroid_2(context)?,
-                Tile::metal(context)?,
-            ]
-            .into_iter()
-            .map(|t| (t.id.clone(), t))
-            .collect(),
-            items: vec![Item::metal(context)?, Item::refined_metal(context)?]
-                .into_iter()
-                .map(|ref i @ (ref item, _)| (item.id.clone(), i.clone()))
-                .collect(),
-            constructs: vec![
-                Construct::miner(context, (em, cm))?,
-                Construct::furnace(context, (em, cm))?,
-            ]
-            .into_iter()
-            .flatten()
-            .chain(vec![
-                Construct::left_router(context)?,
-                Construct::right_router(context)?,
-                Construct::left_splitter(context)?,
-                Construct::right_splitter(context)?,
-            ])
-            .map(|ref o @ (ref c, _)| (c.id.clone(), o.clone()))
-            .collect(),
+            generator,
+            tiles,
+            items,
+            constructs,
+            recipes,
+            hotbar,
             space: Tile::space(context)?,
             mode: MENU_MODE,
         })
     }
 
-    pub fn save(&self) -> anyhow::Result<()> {
-        let content = serde_json::to_string(&self.save_data)?;
+    pub fn open(&mut self, world: World, save_data: SaveData) -> anyhow::Result<()> {
+        self.generator = Arc::new(Generator::load(save_data.seed, save_data.options)?);
+        self.save_data = save_data;
+        self.world = Some(world);
+
+        Ok(())
+    }
 
-        fs::write(&*SAVE_PATH, content)?;
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(world) = &self.world {
+            self.save_data.save(world)?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,172 @@
+use super::SaveData;
+use crate::{chunk::Region, SAVE_DIR};
+use hex::anyhow;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_WORLD: &str = "default";
+pub const LEGACY_SAVE_DIR: &str = "save";
+pub const MAX_NAME_LEN: usize = 32;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct World {
+    pub name: String,
+}
+
+impl World {
+    pub fn new(name: &str) -> anyhow::Result<Self> {
+        let name = name.trim();
+
+        if name.is_empty() {
+            anyhow::bail!("World names cannot be empty");
+        }
+
+        if name.chars().count() > MAX_NAME_LEN {
+            anyhow::bail!("World names cannot be longer than {MAX_NAME_LEN} characters");
+        }
+
+        if !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+        {
+            anyhow::bail!("World names can only contain letters, numbers, spaces, - and _");
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+        })
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        PathBuf::from(SAVE_DIR).join(&self.name)
+    }
+
+    pub fn save_path(&self) -> PathBuf {
+        self.dir().join("map.json")
+    }
+
+    pub fn regions_dir(&self) -> PathBuf {
+        Region::dir(&self.dir())
+    }
+
+    pub fn backups_dir(&self) -> PathBuf {
+        self.dir().join("backups")
+    }
+
+    pub fn backup(&self, count: usize) -> anyhow::Result<()> {
+        if count == 0 || !self.exists() {
+            return Ok(());
+        }
+
+        let dir = self.backups_dir();
+        let oldest = dir.join(count.to_string());
+
+        if Path::exists(&oldest) {
+            fs::remove_dir_all(oldest)?;
+        }
+
+        for i in (1..count).rev() {
+            let backup = dir.join(i.to_string());
+
+            if Path::exists(&backup) {
+                fs::rename(backup, dir.join((i + 1).to_string()))?;
+            }
+        }
+
+        let backup = dir.join("1");
+        let regions = Region::dir(&backup);
+
+        fs::create_dir_all(&regions)?;
+
+        Self::link_or_copy(&self.save_path(), &backup.join("map.json"))?;
+
+        for pos in Region::saved(&self.regions_dir())? {
+            let file = Region::region_file(pos);
+
+            Self::link_or_copy(&self.regions_dir().join(&file), &regions.join(file))?;
+        }
+
+        Ok(())
+    }
+
+    // Saves are always replaced by renaming a new file over the old one, so a
+    // hard link keeps pointing at the backed up contents.
+    fn link_or_copy(from: &Path, to: &Path) -> anyhow::Result<()> {
+        if fs::hard_link(from, to).is_err() {
+            fs::copy(from, to)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn exists(&self) -> bool {
+        Path::exists(&self.save_path())
+    }
+
+    pub fn list() -> anyhow::Result<Vec<Self>> {
+        let mut worlds = Vec::new();
+
+        for entry in fs::read_dir(SAVE_DIR)? {
+            if let Some(world) = entry?
+                .file_name()
+                .to_str()
+                .and_then(|n| Self::new(n).ok())
+                .filter(|w| w.exists())
+            {
+                worlds.push(world);
+            }
+        }
+
+        worlds.sort_by_key(|w| w.name.to_lowercase());
+
+        Ok(worlds)
+    }
+
+    pub fn create(name: &str, save_data: &SaveData) -> anyhow::Result<Self> {
+        let world = Self::new(name)?;
+
+        if Path::exists(&world.dir()) {
+            anyhow::bail!("World \"{}\" already exists", world.name);
+        }
+
+        fs::create_dir_all(world.regions_dir())?;
+
+        save_data.save(&world)?;
+
+        Ok(world)
+    }
+
+    pub fn rename(&self, name: &str) -> anyhow::Result<Self> {
+        let world = Self::new(name)?;
+
+        if world == *self {
+            return Ok(world);
+        }
+
+        if Path::exists(&world.dir()) {
+            anyhow::bail!("World \"{}\" already exists", world.name);
+        }
+
+        fs::rename(self.dir(), world.dir())?;
+
+        Ok(world)
+    }
+
+    pub fn delete(&self) -> anyhow::Result<()> {
+        fs::remove_dir_all(self.dir())?;
+
+        Ok(())
+    }
+
+    pub fn migrate_legacy() -> anyhow::Result<()> {
+        let world = Self::new(DEFAULT_WORLD)?;
+
+        if Path::exists(Path::new(LEGACY_SAVE_DIR)) && !Path::exists(&world.dir()) {
+            fs::rename(LEGACY_SAVE_DIR, world.dir())?;
+        }
+
+        Ok(())
+    }
+}
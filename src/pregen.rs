@@ -0,0 +1,158 @@
+use crate::{
+    chunk::{ChunkManager, Generator, Region},
+    player::{world::DEFAULT_WORLD, SaveData, World},
+};
+use hex::anyhow::{self, Context};
+use rayon::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+pub struct Pregen {
+    pub world: World,
+    pub seed: Option<u32>,
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+}
+
+impl Pregen {
+    pub fn parse<I>(args: I) -> anyhow::Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut world = World::new(DEFAULT_WORLD)?;
+        let mut seed = None;
+        let mut from = None;
+        let mut to = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .with_context(|| format!("Missing value for \"{arg}\""))?;
+
+            match arg.as_str() {
+                "--world" => world = World::new(&value)?,
+                "--seed" => {
+                    seed = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("Invalid seed \"{value}\""))?,
+                    )
+                }
+                "--from" => from = Some(Self::parse_pos(&value)?),
+                "--to" => to = Some(Self::parse_pos(&value)?),
+                _ => anyhow::bail!("Unknown argument \"{arg}\""),
+            }
+        }
+
+        let (from, to) = (
+            from.context("Missing \"--from x,y\"")?,
+            to.context("Missing \"--to x,y\"")?,
+        );
+
+        Ok(Self {
+            world,
+            seed,
+            from: (from.0.min(to.0), from.1.min(to.1)),
+            to: (from.0.max(to.0), from.1.max(to.1)),
+        })
+    }
+
+    fn parse_pos(value: &str) -> anyhow::Result<(i32, i32)> {
+        value
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+            .with_context(|| format!("Invalid chunk position \"{value}\", expected x,y"))
+    }
+
+    pub fn run(&self) -> anyhow::Result<()> {
+        let save_data = if self.world.exists() {
+            SaveData::load(&self.world)?
+        } else {
+            SaveData::load(&World::create(
+                &self.world.name,
+                &SaveData::new(
+                    self.seed.unwrap_or_else(rand::random),
+                    None,
+                    Default::default(),
+                ),
+            )?)?
+        };
+
+        if let Some(seed) = self.seed.filter(|s| *s != save_data.seed) {
+            anyhow::bail!(
+                "World \"{}\" uses seed {}, not {seed}",
+                self.world.name,
+                save_data.seed
+            );
+        }
+
+        let generator = Generator::load(save_data.seed, save_data.options)?;
+        let dir = self.world.regions_dir();
+        let mut regions: HashMap<_, Vec<_>> = HashMap::new();
+
+        for x in self.from.0..=self.to.0 {
+            for y in self.from.1..=self.to.1 {
+                regions
+                    .entry(Region::region_pos((x, y)))
+                    .or_default()
+                    .push((x, y));
+            }
+        }
+
+        let total: usize = regions.values().map(|c| c.len()).sum();
+        let done = AtomicUsize::new(0);
+        let generated = AtomicUsize::new(0);
+        let start = Instant::now();
+
+        println!(
+            "Generating {total} chunks in {} regions of \"{}\" with seed {}",
+            regions.len(),
+            self.world.name,
+            save_data.seed
+        );
+
+        regions
+            .into_par_iter()
+            .try_for_each(|(pos, chunks)| -> anyhow::Result<()> {
+                let region_start = Instant::now();
+                let mut region = Region::load(&dir, pos)?;
+                let mut count = 0;
+
+                for chunk in chunks.iter().cloned() {
+                    if !region.chunks.contains_key(&chunk) {
+                        region.insert(chunk, &ChunkManager::gen_chunk(chunk, &generator));
+
+                        count += 1;
+                    }
+                }
+
+                region.save(&dir, pos)?;
+
+                let done = done.fetch_add(chunks.len(), Ordering::Relaxed) + chunks.len();
+
+                generated.fetch_add(count, Ordering::Relaxed);
+
+                println!(
+                    "[{done}/{total}] Region {pos:?}: generated {count} chunks in {:.2?}",
+                    region_start.elapsed()
+                );
+
+                Ok(())
+            })?;
+
+        let elapsed = start.elapsed();
+        let generated = generated.into_inner();
+
+        println!(
+            "Generated {generated} chunks ({} already existed) in {elapsed:.2?} ({:.1} chunks/s)",
+            total - generated,
+            generated as f64 / elapsed.as_secs_f64()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+pub mod spatial_manager;
+
+pub use spatial_manager::SpatialManager;
+
+use crate::chunk::CHUNK_SIZE;
+use hex::{
+    ecs::{component_manager::Component, Id},
+    math::Vec2d,
+};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Default)]
+pub struct SpatialIndex {
+    pub tiles: HashMap<(i32, i32), HashSet<Id>>,
+    pub positions: HashMap<Id, Vec2d>,
+}
+
+impl SpatialIndex {
+    pub fn tile_pos(position: Vec2d) -> (i32, i32) {
+        (position.x().floor() as i32, position.y().floor() as i32)
+    }
+
+    pub fn insert(&mut self, e: Id, position: Vec2d) {
+        let tile = Self::tile_pos(position);
+
+        if let Some(old) = self.positions.insert(e, position) {
+            let old = Self::tile_pos(old);
+
+            if old == tile {
+                return;
+            }
+
+            self.remove_from(e, old);
+        }
+
+        self.tiles.entry(tile).or_default().insert(e);
+    }
+
+    pub fn remove(&mut self, e: Id) {
+        if let Some(position) = self.positions.remove(&e) {
+            self.remove_from(e, Self::tile_pos(position));
+        }
+    }
+
+    fn remove_from(&mut self, e: Id, tile: (i32, i32)) {
+        if let Some(entities) = self.tiles.get_mut(&tile) {
+            entities.remove(&e);
+
+            if entities.is_empty() {
+                self.tiles.remove(&tile);
+            }
+        }
+    }
+
+    pub fn tile(&self, tile: (i32, i32)) -> Vec<Id> {
+        self.tiles
+            .get(&tile)
+            .map(|entities| entities.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn area(&self, min: (i32, i32), max: (i32, i32)) -> Vec<Id> {
+        let mut entities = Vec::new();
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(tile) = self.tiles.get(&(x, y)) {
+                    entities.extend(tile.iter().cloned());
+                }
+            }
+        }
+
+        entities
+    }
+
+    pub fn chunk(&self, (x, y): (i32, i32)) -> Vec<Id> {
+        let size = CHUNK_SIZE as i32;
+        let min = ((x - 1) * size, (y - 1) * size);
+
+        self.area(min, (min.0 + size - 1, min.1 + size - 1))
+    }
+
+    pub fn radius(&self, position: Vec2d, radius: f32) -> Vec<Id> {
+        let min = Self::tile_pos(position - Vec2d([radius; 2]));
+        let max = Self::tile_pos(position + Vec2d([radius; 2]));
+
+        self.area(min, max)
+            .into_iter()
+            .filter(|e| {
+                self.positions
+                    .get(e)
+                    .map(|p| (*p - position).magnitude() <= radius)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+impl Component for SpatialIndex {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut entities: Vec<Id>) -> Vec<Id> {
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn insert_moves_and_removes_entities() {
+        let mut index = SpatialIndex::default();
+
+        index.insert(1, Vec2d::new(0.5, 0.5));
+        index.insert(2, Vec2d::new(0.25, 0.75));
+
+        assert_eq!(sorted(index.tile((0, 0))), vec![1, 2]);
+
+        index.insert(1, Vec2d::new(3.5, -1.5));
+
+        assert_eq!(index.tile((0, 0)), vec![2]);
+        assert_eq!(index.tile((3, -2)), vec![1]);
+
+        index.remove(2);
+        index.remove(3);
+
+        assert!(index.tile((0, 0)).is_empty());
+        assert!(!index.tiles.contains_key(&(0, 0)));
+        assert_eq!(index.positions.len(), 1);
+    }
+
+    #[test]
+    fn area_and_chunk_cover_their_tiles() {
+        let size = CHUNK_SIZE as f32;
+        let mut index = SpatialIndex::default();
+
+        index.insert(1, Vec2d::new(0.5, 0.5));
+        index.insert(2, Vec2d::new(size - 0.5, size - 0.5));
+        index.insert(3, Vec2d::new(size + 0.5, 0.5));
+        index.insert(4, Vec2d::new(-0.5, 0.5));
+
+        assert_eq!(sorted(index.area((0, 0), (1, 1))), vec![1]);
+        assert_eq!(
+            sorted(index.area((-1, 0), (CHUNK_SIZE as i32, 0))),
+            vec![1, 3, 4]
+        );
+        assert_eq!(sorted(index.chunk((1, 1))), vec![1, 2]);
+        assert_eq!(sorted(index.chunk((2, 1))), vec![3]);
+        assert_eq!(sorted(index.chunk((0, 1))), vec![4]);
+    }
+
+    #[test]
+    fn radius_filters_by_distance() {
+        let mut index = SpatialIndex::default();
+
+        index.insert(1, Vec2d::new(0.5, 0.5));
+        index.insert(2, Vec2d::new(2.0, 0.5));
+        index.insert(3, Vec2d::new(2.0, 2.0));
+
+        assert_eq!(sorted(index.radius(Vec2d::new(0.5, 0.5), 1.5)), vec![1, 2]);
+        assert_eq!(
+            sorted(index.radius(Vec2d::new(0.5, 0.5), 2.5)),
+            vec![1, 2, 3]
+        );
+        assert!(index.radius(Vec2d::new(10.0, 10.0), 1.0).is_empty());
+    }
+}
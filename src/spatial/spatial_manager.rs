@@ -0,0 +1,72 @@
+use super::SpatialIndex;
+use crate::{
+    construct::{Construct, Item},
+    tag::Tag,
+};
+use hex::{
+    anyhow,
+    components::Transform,
+    ecs::{ev::Control, system_manager::System, ComponentManager, Context, EntityManager, Ev, Id},
+    glium::glutin::event::Event,
+};
+use hex_physics::Physical;
+
+pub struct SpatialManager {
+    index: Id,
+}
+
+impl SpatialManager {
+    pub fn new((em, cm): (&mut EntityManager, &mut ComponentManager)) -> Self {
+        let index = em.add();
+
+        cm.add(index, SpatialIndex::default(), em);
+        cm.add(index, Tag::new("spatial_index"), em);
+
+        Self { index }
+    }
+}
+
+impl System for SpatialManager {
+    fn update(
+        &mut self,
+        ev: &mut Ev,
+        _context: &mut Context,
+        (em, cm): (&mut EntityManager, &mut ComponentManager),
+    ) -> anyhow::Result<()> {
+        if let Ev::Event(Control {
+            event: Event::MainEventsCleared,
+            flow: _,
+        }) = ev
+        {
+            let moved: Vec<_> = em
+                .entities()
+                .filter(|e| cm.get::<Item>(*e).is_some() && cm.get::<Physical>(*e).is_some())
+                .filter_map(|e| Some((e, cm.get::<Transform>(e)?.position())))
+                .collect();
+            let stale: Vec<_> = cm
+                .get::<SpatialIndex>(self.index)
+                .map(|i| {
+                    i.positions
+                        .keys()
+                        .filter(|e| {
+                            cm.get::<Item>(**e).is_none() && cm.get::<Construct>(**e).is_none()
+                        })
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            if let Some(index) = cm.get_mut::<SpatialIndex>(self.index) {
+                for (e, position) in moved {
+                    index.insert(e, position);
+                }
+
+                for e in stale {
+                    index.remove(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
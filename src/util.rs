@@ -1,6 +1,6 @@
-use crate::SAVE_DIR;
+use crate::{chunk::Region, player::World, ASSETS_DIR, SAVE_DIR};
 use hex::{
-    anyhow,
+    anyhow::{self, Context},
     assets::Texture,
     glium::{
         texture::{MipmapsOption, RawImage2d},
@@ -9,7 +9,13 @@ use hex::{
     },
     math::Vec2d,
 };
-use std::{fs, io::Cursor, path::PathBuf};
+use hex_ui::ab_glyph::FontRef;
+use serde::de::DeserializeOwned;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Cursor, Write},
+    path::{Path, PathBuf},
+};
 
 pub fn load_texture(display: &Display, p: &[u8]) -> anyhow::Result<Texture> {
     let mut img = image::io::Reader::new(Cursor::new(p));
@@ -32,12 +38,94 @@ pub fn load_texture(display: &Display, p: &[u8]) -> anyhow::Result<Texture> {
     )
 }
 
+pub fn text_texture(
+    display: &Display,
+    font: &FontRef<'static>,
+    text: &str,
+    color: [f32; 4],
+) -> anyhow::Result<Texture> {
+    Texture::text(
+        display,
+        text,
+        font.clone(),
+        (50.0, 0, 1000.0),
+        color,
+        MipmapsOption::AutoGeneratedMipmaps,
+        SamplerBehavior {
+            magnify_filter: MagnifySamplerFilter::Nearest,
+            minify_filter: MinifySamplerFilter::Nearest,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn fit_text((width, height): (u32, u32), scale: f32) -> Vec2d {
+    let h = scale / 10.0;
+    let w = h * width as f32 / height.max(1) as f32;
+    let max = scale * 2.0 / 5.0;
+
+    if w > max {
+        Vec2d::new(max, h * max / w)
+    } else {
+        Vec2d::new(w, h)
+    }
+}
+
+pub fn asset_texture_path(path: &str) -> PathBuf {
+    PathBuf::from(ASSETS_DIR).join("textures").join(path)
+}
+
+pub fn load_asset_texture(display: &Display, path: &str) -> anyhow::Result<Texture> {
+    let path = asset_texture_path(path);
+    let content =
+        fs::read(&path).with_context(|| format!("Failed to read texture {}", path.display()))?;
+
+    load_texture(display, &content)
+        .with_context(|| format!("Failed to decode texture {}", path.display()))
+}
+
+pub fn load_manifest<T>(name: &str) -> anyhow::Result<Vec<T>>
+where
+    T: DeserializeOwned,
+{
+    let path = PathBuf::from(ASSETS_DIR).join(name);
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+
+    serde_json::from_str(&content).with_context(|| format!("Invalid manifest {}", path.display()))
+}
+
+pub fn write_atomic(path: &Path, content: &[u8]) -> anyhow::Result<()> {
+    write_atomic_with(path, |w| Ok(w.write_all(content)?))
+}
+
+pub fn write_atomic_with<F>(path: &Path, f: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> anyhow::Result<()>,
+{
+    let mut tmp = path.as_os_str().to_owned();
+
+    tmp.push(".tmp");
+
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+
+    f(&mut writer)?;
+
+    writer.into_inner()?.sync_all()?;
+
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
 pub fn setup_directories() -> anyhow::Result<()> {
     fs::create_dir_all(SAVE_DIR)?;
 
-    let chunks_dir = PathBuf::from(SAVE_DIR).join("chunks");
+    World::migrate_legacy()?;
 
-    fs::create_dir_all(chunks_dir)?;
+    for world in World::list()? {
+        Region::convert_chunks(&world.dir())?;
+    }
 
     Ok(())
 }
@@ -57,6 +145,20 @@ pub fn mouse_pos_world(
     ))
 }
 
+pub fn in_bounds(size_limit: Option<u32>, pos: Vec2d) -> bool {
+    size_limit
+        .map(|l| pos.x().abs() <= l as f32 && pos.y().abs() <= l as f32)
+        .unwrap_or(true)
+}
+
+pub fn clamp_bounds(size_limit: Option<u32>, pos: Vec2d) -> Vec2d {
+    if let Some(l) = size_limit.map(|l| l as f32) {
+        Vec2d::new(pos.x().clamp(-l, l), pos.y().clamp(-l, l))
+    } else {
+        pos
+    }
+}
+
 pub fn lerp(f1: f32, f2: f32, t: f32) -> f32 {
     f1 * t + f2 * t
 }